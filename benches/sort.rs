@@ -0,0 +1,61 @@
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use sort_chinese_name::{Collation, NameSorter};
+
+#[path = "../tests/common/mod.rs"]
+mod common;
+
+use common::Rng;
+
+const SURNAMES: &str = "王李张刘陈杨黄赵吴周徐孙马朱胡郭何高林罗郑梁谢宋唐许韩冯邓曹彭曾肖田董袁潘于蒋蔡余杜叶程苏魏吕丁任沈姚卢姜崔钟谭陆汪范金石廖贾夏韦付方白邹孟熊秦邱江尹薛闫段雷侯龙史陶黎贺顾毛郝龚邵万钱严覃武戴莫孔向汤";
+const GIVEN: &str = "伟芳娜敏静丽强磊军洋勇艳杰娟涛明超秀霞平刚桂英华玉兰萍红鹏建文辉力飞斌宇浩凯健俊帆帅旭宁龙林欣佳怡雪梅琳晨阳博文轩子涵梓睿";
+
+/// Deterministic pseudo-random names, two or three characters long, with
+/// the odd compound surname.
+fn names(count: usize) -> Vec<String> {
+    let surnames: Vec<char> = SURNAMES.chars().collect();
+    let given: Vec<char> = GIVEN.chars().collect();
+    let mut rng = Rng::new(0x853c_49e6_748f_ea9b);
+    (0..count)
+        .map(|_| {
+            let mut name = String::new();
+            if rng.below(50) == 0 {
+                name.push_str("欧阳");
+            } else {
+                name.push(surnames[rng.below(surnames.len())]);
+            }
+            for _ in 0..1 + rng.below(2) {
+                name.push(given[rng.below(given.len())]);
+            }
+            name
+        })
+        .collect()
+}
+
+fn sort(c: &mut Criterion) {
+    for collation in [Collation::Strokes, Collation::Pinyin] {
+        let sorter = NameSorter::default().with_collation(collation);
+        let mut group = c.benchmark_group(format!("sort/{:?}", collation));
+        group.sample_size(10);
+        for count in [10_000, 100_000] {
+            let input = names(count);
+            group.bench_with_input(BenchmarkId::new("compare", count), &input, |b, input| {
+                b.iter_batched_ref(
+                    || input.clone(),
+                    |names| names.sort_by(|x, y| sorter.compare(x, y)),
+                    BatchSize::LargeInput,
+                )
+            });
+            group.bench_with_input(BenchmarkId::new("cached_keys", count), &input, |b, input| {
+                b.iter_batched_ref(|| input.clone(), |names| sorter.sort(names), BatchSize::LargeInput)
+            });
+            #[cfg(feature = "parallel")]
+            group.bench_with_input(BenchmarkId::new("parallel", count), &input, |b, input| {
+                b.iter_batched_ref(|| input.clone(), |names| sorter.par_sort(names), BatchSize::LargeInput)
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, sort);
+criterion_main!(benches);
@@ -0,0 +1,76 @@
+use std::env;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+fn main() {
+    println!("cargo:rerun-if-changed=data.json");
+    println!("cargo:rerun-if-changed=strokes_supplement.json");
+    println!("cargo:rerun-if-changed=pinyin.txt");
+    let out_dir = env::var("OUT_DIR").unwrap();
+
+    write_word_dict(Path::new(&out_dir).join("word_dict.rs"));
+    write_pinyin_dict(Path::new(&out_dir).join("pinyin_dict.rs"));
+}
+
+fn write_word_dict(out: PathBuf) {
+    let mut data: Vec<(String, String)> =
+        serde_json::from_slice(&fs::read("data.json").expect("read data.json")).expect("parse data.json");
+    // Characters data.json lacks, such as 蝦, in the same format.
+    let supplement: Vec<(String, String)> = serde_json::from_slice(
+        &fs::read("strokes_supplement.json").expect("read strokes_supplement.json"),
+    )
+    .expect("parse strokes_supplement.json");
+    data.extend(supplement);
+
+    let mut entries: Vec<(char, String)> = data
+        .into_iter()
+        .filter_map(|(word, order)| {
+            let mut chars = word.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Some((c, order)),
+                _ => None,
+            }
+        })
+        .collect();
+    // Later entries win, as they do when the file is loaded into a map at runtime.
+    entries.reverse();
+    entries.sort_by_key(|&(c, _)| c);
+    entries.dedup_by_key(|&mut (c, _)| c);
+
+    let mut out = BufWriter::new(File::create(out).unwrap());
+    writeln!(out, "pub(crate) static WORD_DICT: &[(char, &str)] = &[").unwrap();
+    for (c, order) in &entries {
+        writeln!(out, "    ({:?}, {:?}),", c, order).unwrap();
+    }
+    writeln!(out, "];").unwrap();
+}
+
+fn write_pinyin_dict(out: PathBuf) {
+    let text = fs::read_to_string("pinyin.txt").expect("read pinyin.txt");
+
+    let mut entries: Vec<(char, &str, u8)> = text
+        .lines()
+        .map(|line| line.split('#').next().unwrap().trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let (c, reading) = line.split_once(' ').expect("pinyin.txt: character and reading");
+            let (syllable, tone) = reading.split_at(reading.len() - 1);
+            (
+                c.chars().next().expect("pinyin.txt: character"),
+                syllable,
+                tone.parse().expect("pinyin.txt: tone number"),
+            )
+        })
+        .collect();
+    entries.reverse();
+    entries.sort_by_key(|&(c, _, _)| c);
+    entries.dedup_by_key(|&mut (c, _, _)| c);
+
+    let mut out = BufWriter::new(File::create(out).unwrap());
+    writeln!(out, "pub(crate) static PINYIN_DICT: &[(char, &str, u8)] = &[").unwrap();
+    for (c, syllable, tone) in &entries {
+        writeln!(out, "    ({:?}, {:?}, {}),", c, syllable, tone).unwrap();
+    }
+    writeln!(out, "];").unwrap();
+}
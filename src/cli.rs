@@ -0,0 +1,489 @@
+use sort_chinese_name::{
+    Collation, Column, ColumnRef, FieldPath, GroupStyle, KeyKind, Normalization, OutputFormat, RecordFormat, TableFormat,
+    TableKey, TieBreak, UnknownPolicy,
+};
+use std::path::PathBuf;
+
+pub const USAGE: &str = "\
+Sort Chinese names by the strokes of their characters.
+
+Usage: SortChineseName [OPTIONS] [INPUT]
+
+Arguments:
+  [INPUT]  File with one name per line, or a table with --input-format, `-` reads
+           stdin [default: names.txt]
+
+Options:
+      --dict <FILE>      Stroke dictionary replacing the built-in one
+      --surnames <FILE>  Compound surname list replacing the built-in one
+      --pinyin <FILE>    Pinyin dictionary replacing the built-in one
+      --surname-pinyin <FILE>
+                         Surname readings replacing the built-in ones
+      --variants <FILE>  Variant table replacing the built-in traditional/simplified
+                         and 异体字 one
+      --input-format <FORMAT>
+                         lines, or csv, tsv or xlsx to sort the rows of a table by their
+                         name column and write them back with every column, or json
+                         (an array) or jsonl (one per line) to sort records by the
+                         field given with --name-field [default: lines]
+      --name-field <PATH>
+                         Dot-separated path to the name in json or jsonl records, such
+                         as `person.name`, or `.` for plain strings [default: name]
+      --sheet <NAME>     Sheet of xlsx input to sort [default: the first sheet]
+      --name-column <COLUMN>
+                         Column of csv or tsv input holding the names, a number counting
+                         from 1 or a header name [default: 1]
+      --no-header        csv or tsv input has no header row
+      --sort-by <KEYS>   Sort csv or tsv rows by several columns in turn, each given as
+                         COLUMN[:text|number|name][:asc|desc], such as
+                         `部门,年级:number:desc,姓名:name`; the first name column is the
+                         name column unless --name-column is given [default: the name column]
+      --add-columns <LIST>
+                         Columns to append to csv, tsv or xlsx rows, computed from the
+                         name: surname, given, strokes, pinyin, order, key, canonical
+  -o, --output <FILE>    Output file, `-` writes stdout [default: out.txt]
+  -f, --format <FORMAT>  lines, csv, tsv, json, grouped, grouped-markdown and
+                         grouped-html for sections headed by surname stroke count, or
+                         keys for name<TAB>hex sort key lines [default: lines];
+                         csv, tsv or xlsx input is written as csv, tsv or xlsx, and
+                         json or jsonl input as json or jsonl [default: as read]
+      --separator <SEP>  Write all names on one line joined by SEP (`\t` and `\n` are unescaped)
+      --columns <LIST>   Comma-separated columns for csv, tsv and json output:
+                         name, surname, given, strokes (of the surname), pinyin, order,
+                         key (hex sort key), canonical (variants folded) [default: name]
+      --surname-delimiter <CHAR>
+                         Marks the end of the surname in a name, as in `司|马光`; an
+                         empty value turns this off [default: |]
+      --order <ORDER>    strokes, gb13418 for the standard 姓氏笔画 order, stroke-count to
+                         compare stroke counts only and keep the input order of ties,
+                         or pinyin [default: strokes]
+      --ties <TIE_BREAK> Order of names that sort equal: input, reverse, code-point,
+                         or error to fail [default: input]
+      --unknown <POLICY> Characters missing from the dictionary: error, last, first,
+                         or a stroke count to sort them by [default: last]
+      --report-unknown   List missing characters and the names using them on stderr
+      --fold-variants    Sort traditional and variant characters by their standard
+                         simplified forms, so 陳 sorts as 陈 and 峯 as 峰
+      --report-variants  List names that differ only by such variants on stderr
+      --normalize <FORM> Normalize characters before looking them up: nfc, which also
+                         maps Kangxi radicals such as ⾦ to 金, nfkc, or none
+                         [default: nfc]
+      --report-normalized
+                         List names that normalization changes on stderr
+      --run-size <N>     Sort at most N names in memory at a time, spilling sorted runs
+                         to temporary files and merging them; lines format only
+  -h, --help             Print help
+  -V, --version          Print version
+
+Exit status: 0 on success, 1 if reading, sorting or writing failed, 2 on invalid arguments.
+";
+
+pub enum Command {
+    Run(Box<Args>),
+    Help,
+    Version,
+}
+
+pub struct Args {
+    pub dict: Option<PathBuf>,
+    pub surnames: Option<PathBuf>,
+    pub pinyin: Option<PathBuf>,
+    pub surname_pinyin: Option<PathBuf>,
+    pub variants: Option<PathBuf>,
+    pub input: PathBuf,
+    pub output: PathBuf,
+    pub format: OutputFormat,
+    pub surname_delimiter: Option<char>,
+    pub order: Collation,
+    pub ties: TieBreak,
+    pub unknown: UnknownPolicy,
+    pub report_unknown: bool,
+    pub fold_variants: bool,
+    pub report_variants: bool,
+    pub normalization: Normalization,
+    pub report_normalized: bool,
+    pub run_size: Option<usize>,
+    pub table: Option<TableArgs>,
+    pub records: Option<RecordArgs>,
+}
+
+/// How to read and write csv, tsv or xlsx input.
+pub struct TableArgs {
+    pub input: TableFile,
+    pub output: TableFile,
+    #[cfg(feature = "xlsx")]
+    pub sheet: Option<String>,
+    pub name_column: ColumnRef,
+    pub has_header: bool,
+    pub sort_by: Vec<TableKey>,
+    pub add_columns: Vec<Column>,
+}
+
+/// How to read and write json or jsonl input.
+pub struct RecordArgs {
+    pub input: RecordFormat,
+    pub output: RecordFormat,
+    pub name_field: FieldPath,
+}
+
+enum InputFormat {
+    Lines,
+    Table(TableFile),
+    Records(RecordFormat),
+}
+
+/// Only parsed as [`Xlsx`](TableFile::Xlsx) when built with the `xlsx` feature.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum TableFile {
+    Text(TableFormat),
+    Xlsx,
+}
+
+impl Default for Args {
+    fn default() -> Self {
+        Args {
+            dict: None,
+            surnames: None,
+            pinyin: None,
+            surname_pinyin: None,
+            variants: None,
+            input: PathBuf::from("names.txt"),
+            output: PathBuf::from("out.txt"),
+            format: OutputFormat::Lines,
+            surname_delimiter: Some('|'),
+            order: Collation::default(),
+            ties: TieBreak::default(),
+            unknown: UnknownPolicy::default(),
+            report_unknown: false,
+            fold_variants: false,
+            report_variants: false,
+            normalization: Normalization::default(),
+            report_normalized: false,
+            run_size: None,
+            table: None,
+            records: None,
+        }
+    }
+}
+
+pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Command, String> {
+    let mut parsed = Args::default();
+    let mut input = None;
+    let mut format = None;
+    let mut separator = None;
+    let mut columns = None;
+    let mut input_format = None;
+    let mut name_column = None;
+    let mut no_header = false;
+    let mut sort_by = None;
+    let mut sheet = None;
+    let mut add_columns = None;
+    let mut name_field = None;
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+            _ => (arg.clone(), None),
+        };
+        let mut value = || {
+            inline_value
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("option `{}` requires a value", flag))
+        };
+
+        match flag.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-V" | "--version" => return Ok(Command::Version),
+            "--dict" => parsed.dict = Some(value()?.into()),
+            "--surnames" => parsed.surnames = Some(value()?.into()),
+            "--pinyin" => parsed.pinyin = Some(value()?.into()),
+            "--surname-pinyin" => parsed.surname_pinyin = Some(value()?.into()),
+            "--variants" => parsed.variants = Some(value()?.into()),
+            "--input-format" => input_format = parse_input_format(&value()?)?,
+            "--name-column" => name_column = Some(value()?.parse::<ColumnRef>()?),
+            "--no-header" => no_header = true,
+            "--name-field" => name_field = Some(value()?.parse::<FieldPath>()?),
+            "--sheet" => sheet = Some(value()?),
+            "--add-columns" => add_columns = Some(parse_columns(&value()?)?),
+            "--sort-by" => sort_by = Some(parse_sort_keys(&value()?)?),
+            "-o" | "--output" => parsed.output = value()?.into(),
+            "-f" | "--format" => format = Some(value()?),
+            "--separator" => separator = Some(unescape(&value()?)),
+            "--columns" => columns = Some(parse_columns(&value()?)?),
+            "--surname-delimiter" => parsed.surname_delimiter = parse_delimiter(&value()?)?,
+            "--order" => parsed.order = value()?.parse()?,
+            "--ties" => parsed.ties = value()?.parse()?,
+            "--unknown" => parsed.unknown = value()?.parse()?,
+            "--report-unknown" => parsed.report_unknown = true,
+            "--fold-variants" => parsed.fold_variants = true,
+            "--report-variants" => parsed.report_variants = true,
+            "--normalize" => parsed.normalization = value()?.parse()?,
+            "--report-normalized" => parsed.report_normalized = true,
+            "--run-size" => parsed.run_size = Some(parse_run_size(&value()?)?),
+            "-" => input = set_input(input, arg)?,
+            _ if flag.starts_with('-') => return Err(format!("unknown option `{}`", flag)),
+            _ => input = set_input(input, arg)?,
+        }
+    }
+
+    if let Some(input) = input {
+        parsed.input = input;
+    }
+    let table_options = name_column.is_some() || no_header || sort_by.is_some() || sheet.is_some() || add_columns.is_some();
+    let input_format = input_format.unwrap_or(InputFormat::Lines);
+    if !matches!(input_format, InputFormat::Lines) && parsed.run_size.is_some() {
+        return Err("`--run-size` only sorts lines input".to_string());
+    }
+    if !matches!(input_format, InputFormat::Records(_)) && name_field.is_some() {
+        return Err("`--name-field` requires `--input-format json` or `jsonl`".to_string());
+    }
+    if !matches!(input_format, InputFormat::Table(_)) && table_options {
+        return Err(
+            "`--name-column`, `--no-header`, `--sort-by`, `--sheet` and `--add-columns` require `--input-format csv`, \
+             `tsv` or `xlsx`"
+                .to_string(),
+        );
+    }
+
+    if let InputFormat::Records(input_format) = input_format {
+        let output = match (format.as_deref(), &separator, &columns) {
+            (None, None, None) => input_format,
+            (Some("json"), None, None) => RecordFormat::Json,
+            (Some("jsonl"), None, None) => RecordFormat::JsonLines,
+            _ => return Err("json and jsonl input can only be written as json or jsonl".to_string()),
+        };
+        let name_field = match name_field {
+            Some(name_field) => name_field,
+            None => "name".parse()?,
+        };
+        parsed.records = Some(RecordArgs { input: input_format, output, name_field });
+        return Ok(Command::Run(Box::new(parsed)));
+    }
+    if let InputFormat::Table(input_format) = input_format {
+        let output = match (format.as_deref(), &separator, &columns) {
+            (None, None, None) => input_format,
+            (Some("csv"), None, None) => TableFile::Text(TableFormat::Csv),
+            (Some("tsv"), None, None) => TableFile::Text(TableFormat::Tsv),
+            (Some("xlsx"), None, None) => parse_table_file("xlsx")?,
+            _ => {
+                return Err(
+                    "csv, tsv and xlsx input can only be written as csv, tsv or xlsx, with every column".to_string()
+                )
+            }
+        };
+        if sheet.is_some() && input_format != TableFile::Xlsx {
+            return Err("`--sheet` requires `--input-format xlsx`".to_string());
+        }
+        let sort_by = sort_by.unwrap_or_default();
+        let name_column = name_column
+            .or_else(|| sort_by.iter().find(|key| key.kind == KeyKind::Name).map(|key| key.column.clone()))
+            .unwrap_or(ColumnRef::Index(0));
+        if no_header {
+            let mut columns = sort_by.iter().map(|key| &key.column).chain([&name_column]);
+            if let Some(ColumnRef::Header(name)) = columns.find(|column| matches!(column, ColumnRef::Header(_))) {
+                return Err(format!("`--no-header` needs column numbers, not the header name `{}`", name));
+            }
+        }
+        parsed.table = Some(TableArgs {
+            input: input_format,
+            output,
+            #[cfg(feature = "xlsx")]
+            sheet,
+            name_column,
+            has_header: !no_header,
+            sort_by,
+            add_columns: add_columns.unwrap_or_default(),
+        });
+        return Ok(Command::Run(Box::new(parsed)));
+    }
+
+    parsed.format = match (format.as_deref(), separator) {
+        (Some(_), Some(_)) => return Err("`--separator` cannot be combined with `--format`".to_string()),
+        (None, Some(separator)) => OutputFormat::Separated(separator),
+        (None | Some("lines"), None) => OutputFormat::Lines,
+        (Some("csv"), None) => OutputFormat::Csv(columns.take().unwrap_or_else(|| vec![Column::Name])),
+        (Some("tsv"), None) => OutputFormat::Tsv(columns.take().unwrap_or_else(|| vec![Column::Name])),
+        (Some("json"), None) => OutputFormat::Json(columns.take().unwrap_or_else(|| vec![Column::Name])),
+        (Some("grouped"), None) => OutputFormat::Grouped(GroupStyle::Plain),
+        (Some("grouped-markdown"), None) => OutputFormat::Grouped(GroupStyle::Markdown),
+        (Some("grouped-html"), None) => OutputFormat::Grouped(GroupStyle::Html),
+        (Some("keys"), None) => OutputFormat::Keys,
+        (Some(other), None) => {
+            return Err(format!(
+                "unknown format `{}`, expected lines, csv, tsv, json, grouped, grouped-markdown, grouped-html or keys",
+                other
+            ))
+        }
+    };
+    if matches!(parsed.format, OutputFormat::Grouped(_)) && parsed.order == Collation::Pinyin {
+        return Err("grouped formats are headed by stroke counts and cannot be used with `--order pinyin`".to_string());
+    }
+    if columns.is_some() {
+        return Err("`--columns` requires `--format csv`, `tsv` or `json`".to_string());
+    }
+    if parsed.run_size.is_some() && (parsed.report_variants || parsed.report_normalized) {
+        return Err(
+            "`--report-variants` and `--report-normalized` need all names in memory and cannot be used with `--run-size`"
+                .to_string(),
+        );
+    }
+    if parsed.run_size.is_some() && parsed.format != OutputFormat::Lines {
+        return Err("`--run-size` only writes the lines format".to_string());
+    }
+    Ok(Command::Run(Box::new(parsed)))
+}
+
+fn set_input(current: Option<PathBuf>, arg: String) -> Result<Option<PathBuf>, String> {
+    match current {
+        Some(_) => Err(format!("unexpected argument `{}`, only one input file is accepted", arg)),
+        None => Ok(Some(arg.into())),
+    }
+}
+
+fn parse_columns(list: &str) -> Result<Vec<Column>, String> {
+    list.split(',').map(|column| column.trim().parse()).collect()
+}
+
+fn parse_sort_keys(list: &str) -> Result<Vec<TableKey>, String> {
+    list.split(',').map(str::parse).collect()
+}
+
+fn parse_delimiter(s: &str) -> Result<Option<char>, String> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (None, _) => Ok(None),
+        (Some(c), None) => Ok(Some(c)),
+        _ => Err(format!("surname delimiter `{}` must be a single character", s)),
+    }
+}
+
+fn parse_input_format(s: &str) -> Result<Option<InputFormat>, String> {
+    match s {
+        "lines" => Ok(Some(InputFormat::Lines)),
+        "csv" | "tsv" | "xlsx" => parse_table_file(s).map(|file| Some(InputFormat::Table(file))),
+        "json" | "jsonl" => s.parse().map(|format| Some(InputFormat::Records(format))),
+        _ => Err(format!("unknown input format `{}`, expected lines, csv, tsv, xlsx, json or jsonl", s)),
+    }
+}
+
+fn parse_table_file(s: &str) -> Result<TableFile, String> {
+    match s {
+        "xlsx" if cfg!(feature = "xlsx") => Ok(TableFile::Xlsx),
+        "xlsx" => Err("xlsx support was left out of this build, rebuild with `--features xlsx`".to_string()),
+        _ => s.parse().map(TableFile::Text),
+    }
+}
+
+fn parse_run_size(s: &str) -> Result<usize, String> {
+    match s.parse() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(format!("run size `{}` must be a positive number of names", s)),
+    }
+}
+
+fn unescape(s: &str) -> String {
+    s.replace("\\t", "\t").replace("\\n", "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_args(args: &[&str]) -> Result<Command, String> {
+        parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    fn run(args: &[&str]) -> Args {
+        match parse_args(args) {
+            Ok(Command::Run(args)) => *args,
+            Ok(_) => panic!("{:?} did not parse as a run", args),
+            Err(err) => panic!("{:?}: {}", args, err),
+        }
+    }
+
+    fn error(args: &[&str]) -> String {
+        match parse_args(args) {
+            Err(err) => err,
+            Ok(_) => panic!("{:?} should be rejected", args),
+        }
+    }
+
+    #[test]
+    fn defaults_help_and_version() {
+        let args = run(&[]);
+        assert_eq!(args.input, PathBuf::from("names.txt"));
+        assert_eq!(args.output, PathBuf::from("out.txt"));
+        assert_eq!(args.format, OutputFormat::Lines);
+        assert!(matches!(parse_args(&["a.txt", "--help"]), Ok(Command::Help)));
+        assert!(matches!(parse_args(&["-V"]), Ok(Command::Version)));
+    }
+
+    #[test]
+    fn dash_is_stdin_and_stdout() {
+        let args = run(&["-", "-o", "-"]);
+        assert_eq!(args.input, PathBuf::from("-"));
+        assert_eq!(args.output, PathBuf::from("-"));
+        assert!(error(&["-", "b.txt"]).starts_with("unexpected argument `b.txt`"));
+    }
+
+    #[test]
+    fn inline_values() {
+        let args = run(&["--output=sorted.txt", "--order=pinyin", "--separator=\\t", "--surname-delimiter="]);
+        assert_eq!(args.output, PathBuf::from("sorted.txt"));
+        assert_eq!(args.order, Collation::Pinyin);
+        assert_eq!(args.format, OutputFormat::Separated("\t".to_string()));
+        assert_eq!(args.surname_delimiter, None);
+        assert_eq!(run(&["--separator=="]).format, OutputFormat::Separated("=".to_string()));
+        assert_eq!(
+            run(&["-f", "csv", "--columns=name,strokes"]).format,
+            OutputFormat::Csv(vec![Column::Name, Column::Strokes])
+        );
+    }
+
+    #[test]
+    fn invalid_arguments() {
+        assert_eq!(error(&["-o"]), "option `-o` requires a value");
+        assert_eq!(error(&["--colour"]), "unknown option `--colour`");
+        assert!(error(&["-f", "yaml"]).starts_with("unknown format `yaml`"));
+        assert!(error(&["--run-size", "0"]).starts_with("run size `0`"));
+        assert!(error(&["--surname-delimiter", "||"]).contains("single character"));
+    }
+
+    #[test]
+    fn conflicting_options() {
+        assert_eq!(error(&["-f", "csv", "--separator", ","]), "`--separator` cannot be combined with `--format`");
+        assert_eq!(error(&["--columns", "name"]), "`--columns` requires `--format csv`, `tsv` or `json`");
+        assert_eq!(error(&["--run-size", "10", "-f", "csv"]), "`--run-size` only writes the lines format");
+        assert_eq!(error(&["--run-size", "10", "--input-format", "csv"]), "`--run-size` only sorts lines input");
+        assert!(error(&["--run-size", "10", "--report-variants"]).contains("cannot be used with `--run-size`"));
+        assert!(error(&["--order", "pinyin", "-f", "grouped"]).contains("`--order pinyin`"));
+        assert!(error(&["--name-column", "2"]).starts_with("`--name-column`, `--no-header`"));
+        assert_eq!(error(&["--name-field", "name"]), "`--name-field` requires `--input-format json` or `jsonl`");
+        assert_eq!(error(&["--input-format", "csv", "--sheet", "a"]), "`--sheet` requires `--input-format xlsx`");
+        assert_eq!(
+            error(&["--input-format", "csv", "--no-header", "--name-column", "姓名"]),
+            "`--no-header` needs column numbers, not the header name `姓名`"
+        );
+        assert_eq!(
+            error(&["--input-format", "csv", "--no-header", "--sort-by", "2,部门"]),
+            "`--no-header` needs column numbers, not the header name `部门`"
+        );
+        assert!(error(&["--input-format", "csv", "-f", "json"]).starts_with("csv, tsv and xlsx input"));
+        assert!(error(&["--input-format", "jsonl", "-f", "csv"]).starts_with("json and jsonl input"));
+    }
+
+    #[test]
+    fn table_and_record_input() {
+        let args = run(&["--input-format", "tsv", "--no-header", "--sort-by", "3:number:desc,2:name"]);
+        let table = args.table.unwrap();
+        assert!(table.input == TableFile::Text(TableFormat::Tsv) && table.output == table.input);
+        assert_eq!(table.name_column, ColumnRef::Index(1));
+        assert!(!table.has_header);
+
+        let records = run(&["--input-format=jsonl", "-f", "json", "--name-field", "person.name"]).records.unwrap();
+        assert_eq!((records.input, records.output), (RecordFormat::JsonLines, RecordFormat::Json));
+        assert_eq!(records.name_field.to_string(), "person.name");
+    }
+}
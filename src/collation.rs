@@ -0,0 +1,89 @@
+use std::str::FromStr;
+
+/// The rule used to order two characters, and names made of them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Collation {
+    /// Stroke count, then stroke sequence. Characters sharing a sequence
+    /// compare equal, and a name that runs out first sorts before the longer one.
+    #[default]
+    Strokes,
+    /// 姓氏笔画 order following GB/T 13418: stroke count (笔画数), then the
+    /// shape of the first stroke (起笔笔形) in the order 横 竖 撇 点 折, then
+    /// of the second stroke (次笔笔形) and so on. Characters with identical
+    /// stroke sequences, such as 己 已 巳, are ordered by code point since the
+    /// dictionary carries no structure data. The surname is compared first and
+    /// a name whose characters are a prefix of another's sorts first, so 欧
+    /// comes before 欧阳 and 王一 before 王一一.
+    Gb13418,
+    /// Stroke count of each character and nothing else: names whose
+    /// characters have the same counts compare equal and, since sorting is
+    /// stable, keep their input order. Shorter names sort before their
+    /// extensions.
+    StrokeCountOnly,
+    /// 按拼音排序: each character by its pinyin syllable, then tone, then the
+    /// stroke count and sequence, then code point. The surname is compared
+    /// first, read as a surname where the surname readings list it (单 shàn,
+    /// 曾 zēng), and shorter names sort before their extensions. Under this
+    /// collation a character is unknown when it is missing from the pinyin
+    /// dictionary, and [`UnknownPolicy::Strokes`](crate::UnknownPolicy::Strokes)
+    /// sorts such characters last.
+    Pinyin,
+}
+
+impl Collation {
+    /// The dictionary a character has to be in to be known under this
+    /// collation, as named in reports of unknown characters.
+    pub fn dictionary(self) -> &'static str {
+        match self {
+            Collation::Pinyin => "pinyin dictionary",
+            _ => "stroke dictionary",
+        }
+    }
+}
+
+impl FromStr for Collation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "strokes" => Ok(Collation::Strokes),
+            "gb13418" => Ok(Collation::Gb13418),
+            "stroke-count" => Ok(Collation::StrokeCountOnly),
+            "pinyin" => Ok(Collation::Pinyin),
+            _ => Err(format!("unknown order `{}`, expected strokes, gb13418, stroke-count or pinyin", s)),
+        }
+    }
+}
+
+/// How names that the collation considers equal are ordered.
+///
+/// With [`CodePoint`](TieBreak::CodePoint) every pair of different names is
+/// ordered, so the output is the same however the input was permuted. The
+/// same holds under [`Error`](TieBreak::Error) whenever sorting succeeds.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TieBreak {
+    /// Ties keep the order they had in the input.
+    #[default]
+    InputOrder,
+    /// Ties come out in the reverse of their input order.
+    ReverseInputOrder,
+    /// Ties are ordered by the code points of the names.
+    CodePoint,
+    /// Two different names that tie are an error, see
+    /// [`NameSorter::try_sort`](crate::NameSorter::try_sort).
+    Error,
+}
+
+impl FromStr for TieBreak {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "input" => Ok(TieBreak::InputOrder),
+            "reverse" => Ok(TieBreak::ReverseInputOrder),
+            "code-point" => Ok(TieBreak::CodePoint),
+            "error" => Ok(TieBreak::Error),
+            _ => Err(format!("unknown tie-break `{}`, expected input, reverse, code-point or error", s)),
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::path::Path;
+
+mod embedded {
+    include!(concat!(env!("OUT_DIR"), "/word_dict.rs"));
+}
+
+#[derive(Deserialize)]
+struct JsonData {
+    word: String,
+    order: String,
+}
+
+/// Maps a character to its stroke sequence, written as digits 1-5
+/// (横, 竖, 撇, 点, 折).
+///
+/// Entries are kept sorted by character so lookups are a binary search,
+/// whether the table was compiled in or loaded from a file.
+#[derive(Clone)]
+pub struct StrokeDict {
+    entries: Entries,
+}
+
+#[derive(Clone)]
+enum Entries {
+    Static(&'static [(char, &'static str)]),
+    Owned(Vec<(char, String)>),
+}
+
+impl StrokeDict {
+    /// The dictionary built from `data.json` at compile time.
+    pub fn embedded() -> Self {
+        StrokeDict {
+            entries: Entries::Static(embedded::WORD_DICT),
+        }
+    }
+
+    pub fn get(&self, c: char) -> Option<&str> {
+        match &self.entries {
+            Entries::Static(entries) => entries
+                .binary_search_by_key(&c, |&(c, _)| c)
+                .ok()
+                .map(|i| entries[i].1),
+            Entries::Owned(entries) => entries
+                .binary_search_by_key(&c, |&(c, _)| c)
+                .ok()
+                .map(|i| entries[i].1.as_str()),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match &self.entries {
+            Entries::Static(entries) => entries.len(),
+            Entries::Owned(entries) => entries.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for StrokeDict {
+    fn default() -> Self {
+        StrokeDict::embedded()
+    }
+}
+
+/// Later entries replace earlier ones for the same character.
+impl FromIterator<(char, String)> for StrokeDict {
+    fn from_iter<I: IntoIterator<Item = (char, String)>>(iter: I) -> Self {
+        let mut entries: Vec<(char, String)> = iter.into_iter().collect();
+        entries.reverse();
+        entries.sort_by_key(|&(c, _)| c);
+        entries.dedup_by_key(|&mut (c, _)| c);
+        StrokeDict {
+            entries: Entries::Owned(entries),
+        }
+    }
+}
+
+/// Keys that are not exactly one character are ignored.
+impl From<HashMap<String, String>> for StrokeDict {
+    fn from(map: HashMap<String, String>) -> Self {
+        map.into_iter()
+            .filter_map(|(word, order)| single_char(&word).map(|c| (c, order)))
+            .collect()
+    }
+}
+
+/// Loads a dictionary in the `data.json` format, replacing the embedded one.
+pub fn load_word_dict<P: AsRef<Path>>(path: P) -> io::Result<StrokeDict> {
+    let file = File::open(path)?;
+    let data: Vec<JsonData> = serde_json::from_reader(BufReader::new(file))?;
+    data.into_iter()
+        .map(|d| match single_char(&d.word) {
+            Some(c) => Ok((c, d.order)),
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("dictionary entry `{}` is not a single character", d.word),
+            )),
+        })
+        .collect()
+}
+
+fn single_char(word: &str) -> Option<char> {
+    let mut chars = word.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ => None,
+    }
+}
@@ -0,0 +1,38 @@
+use crate::UnknownChars;
+use std::error::Error;
+use std::fmt;
+
+/// Why [`NameSorter::try_sort`](crate::NameSorter::try_sort) refused to sort.
+#[derive(Debug)]
+pub enum SortError {
+    /// [`UnknownPolicy::Error`](crate::UnknownPolicy::Error) found characters
+    /// missing from the dictionary.
+    UnknownChars(UnknownChars),
+    /// [`TieBreak::Error`](crate::TieBreak::Error) found two different names
+    /// that the collation cannot order.
+    Tie(String, String),
+}
+
+impl fmt::Display for SortError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SortError::UnknownChars(unknown) => unknown.fmt(f),
+            SortError::Tie(a, b) => write!(f, "`{}` and `{}` sort equal", a, b),
+        }
+    }
+}
+
+impl Error for SortError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SortError::UnknownChars(unknown) => Some(unknown),
+            SortError::Tie(..) => None,
+        }
+    }
+}
+
+impl From<UnknownChars> for SortError {
+    fn from(unknown: UnknownChars) -> Self {
+        SortError::UnknownChars(unknown)
+    }
+}
@@ -0,0 +1,219 @@
+use crate::{NameSorter, SortError, TieBreak, UnknownChar, UnknownChars, UnknownPolicy};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Seek, Write};
+
+/// The most runs merged at once, to stay well clear of open file limits.
+/// With more runs than this they are merged in several passes.
+const MAX_RUNS: usize = 128;
+
+/// The most names kept as examples for each unknown character, so the report
+/// does not grow with the input.
+const MAX_UNKNOWN_NAMES: usize = 10;
+
+/// A name with its sort key and input position, as stored in a run file.
+/// Positions are unique, so records sort the way [`NameSorter::sort`] would.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct Record {
+    key: Vec<u8>,
+    seq: u64,
+    name: String,
+}
+
+/// Sorts the names read from `reader`, one per line, and writes them to
+/// `writer` one per line, holding at most `run_len` names in memory. Each
+/// batch of `run_len` names is sorted by its keys and spilled to an anonymous
+/// temporary file (in `$TMPDIR`), and the files are then merged. When the
+/// sort succeeds the output is the same as [`NameSorter::try_sort`] followed
+/// by [`OutputFormat::Lines`](crate::OutputFormat::Lines).
+///
+/// Returns the characters missing from the dictionary, as
+/// [`NameSorter::unknown_chars`] would, but with only the first 10 names that
+/// use each character, so memory stays bounded by `run_len` and the number of
+/// distinct unknown characters. Under [`UnknownPolicy::Error`] these
+/// fail the sort before anything is written; under [`TieBreak::Error`] a tie
+/// fails it part way through the output, so the names already written to
+/// `writer` should be discarded. Sorting errors are returned as
+/// [`io::ErrorKind::InvalidData`] wrapping a [`SortError`].
+pub fn sort_external<R: BufRead, W: Write>(
+    sorter: &NameSorter,
+    reader: R,
+    mut writer: W,
+    run_len: usize,
+) -> io::Result<Vec<UnknownChar>> {
+    let run_len = run_len.max(1);
+    let mut runs = Vec::new();
+    let mut unknown = Unknown::default();
+    let mut batch = Vec::with_capacity(run_len);
+    let mut seq = 0;
+
+    for line in reader.lines() {
+        let line = line?;
+        let name = line.trim();
+        if name.is_empty() {
+            continue;
+        }
+        batch.push(name.to_string());
+        if batch.len() == run_len {
+            runs.push(write_run(sorter, &mut batch, &mut seq, &mut unknown)?);
+        }
+    }
+    if !batch.is_empty() {
+        runs.push(write_run(sorter, &mut batch, &mut seq, &mut unknown)?);
+    }
+
+    let unknown = unknown.chars;
+    if sorter.unknown_policy == UnknownPolicy::Error && !unknown.is_empty() {
+        return Err(invalid_data(UnknownChars(unknown, sorter.collation).into()));
+    }
+
+    while runs.len() > MAX_RUNS {
+        let mut merged = Vec::new();
+        let mut rest = runs.into_iter();
+        loop {
+            let group: Vec<File> = rest.by_ref().take(MAX_RUNS).collect();
+            if group.is_empty() {
+                break;
+            }
+            let mut merge = Merge::new(group)?;
+            let mut run = BufWriter::new(tempfile::tempfile()?);
+            while let Some(record) = merge.next()? {
+                write_record(&mut run, &record)?;
+            }
+            merged.push(run.into_inner().map_err(|err| err.into_error())?);
+        }
+        runs = merged;
+    }
+
+    let mut merge = Merge::new(runs)?;
+    let mut previous: Option<Record> = None;
+    while let Some(record) = merge.next()? {
+        if let Some(previous) = &previous {
+            if sorter.tie_break == TieBreak::Error && previous.key == record.key && previous.name != record.name {
+                return Err(invalid_data(SortError::Tie(previous.name.clone(), record.name)));
+            }
+        }
+        writeln!(writer, "{}", sorter.display_name(&record.name))?;
+        previous = Some(record);
+    }
+    writer.flush()?;
+    Ok(unknown)
+}
+
+/// Unknown characters gathered batch by batch, in order of first appearance,
+/// each with at most [`MAX_UNKNOWN_NAMES`] names.
+#[derive(Default)]
+struct Unknown {
+    chars: Vec<UnknownChar>,
+    index: HashMap<char, usize>,
+}
+
+impl Unknown {
+    fn extend(&mut self, found: Vec<UnknownChar>) {
+        for mut found in found {
+            match self.index.get(&found.ch) {
+                Some(&i) => {
+                    let names = &mut self.chars[i].names;
+                    let room = MAX_UNKNOWN_NAMES.saturating_sub(names.len());
+                    names.extend(found.names.into_iter().take(room));
+                }
+                None => {
+                    found.names.truncate(MAX_UNKNOWN_NAMES);
+                    self.index.insert(found.ch, self.chars.len());
+                    self.chars.push(found);
+                }
+            }
+        }
+    }
+}
+
+/// Sorts `batch` and writes it to a new run file, leaving `batch` empty.
+fn write_run(sorter: &NameSorter, batch: &mut Vec<String>, seq: &mut u64, unknown: &mut Unknown) -> io::Result<File> {
+    unknown.extend(sorter.unknown_chars(batch));
+
+    let mut records: Vec<Record> = batch
+        .drain(..)
+        .map(|name| {
+            let position = *seq;
+            *seq += 1;
+            Record {
+                key: sorter.sort_key(&name),
+                seq: match sorter.tie_break {
+                    TieBreak::ReverseInputOrder => u64::MAX - position,
+                    _ => position,
+                },
+                name,
+            }
+        })
+        .collect();
+    records.sort_unstable();
+
+    let mut run = BufWriter::new(tempfile::tempfile()?);
+    for record in &records {
+        write_record(&mut run, record)?;
+    }
+    run.into_inner().map_err(|err| err.into_error())
+}
+
+fn write_record<W: Write>(writer: &mut W, record: &Record) -> io::Result<()> {
+    writer.write_all(&(record.key.len() as u32).to_le_bytes())?;
+    writer.write_all(&record.key)?;
+    writer.write_all(&record.seq.to_le_bytes())?;
+    writer.write_all(&(record.name.len() as u32).to_le_bytes())?;
+    writer.write_all(record.name.as_bytes())
+}
+
+fn read_record<R: BufRead>(reader: &mut R) -> io::Result<Option<Record>> {
+    if reader.fill_buf()?.is_empty() {
+        return Ok(None);
+    }
+    let key = read_bytes(reader)?;
+    let mut seq = [0; 8];
+    reader.read_exact(&mut seq)?;
+    let name = String::from_utf8(read_bytes(reader)?).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    Ok(Some(Record { key, seq: u64::from_le_bytes(seq), name }))
+}
+
+fn read_bytes<R: Read>(reader: &mut R) -> io::Result<Vec<u8>> {
+    let mut len = [0; 4];
+    reader.read_exact(&mut len)?;
+    let mut bytes = vec![0; u32::from_le_bytes(len) as usize];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// A k-way merge of sorted run files.
+struct Merge {
+    runs: Vec<BufReader<File>>,
+    heads: BinaryHeap<Reverse<(Record, usize)>>,
+}
+
+impl Merge {
+    fn new(files: Vec<File>) -> io::Result<Self> {
+        let mut merge = Merge { runs: Vec::with_capacity(files.len()), heads: BinaryHeap::new() };
+        for mut file in files {
+            file.rewind()?;
+            let mut run = BufReader::new(file);
+            if let Some(record) = read_record(&mut run)? {
+                merge.heads.push(Reverse((record, merge.runs.len())));
+            }
+            merge.runs.push(run);
+        }
+        Ok(merge)
+    }
+
+    fn next(&mut self) -> io::Result<Option<Record>> {
+        let Some(Reverse((record, run))) = self.heads.pop() else {
+            return Ok(None);
+        };
+        if let Some(next) = read_record(&mut self.runs[run])? {
+            self.heads.push(Reverse((next, run)));
+        }
+        Ok(Some(record))
+    }
+}
+
+fn invalid_data(err: SortError) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
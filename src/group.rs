@@ -0,0 +1,112 @@
+use crate::{Collation, NameSorter};
+use std::io::{self, Write};
+
+/// Markup for [`OutputFormat::Grouped`](crate::OutputFormat::Grouped).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GroupStyle {
+    /// A heading line, then one name per line, with a blank line between sections.
+    Plain,
+    /// `##` headings followed by a bullet list.
+    Markdown,
+    /// `<h2>` headings followed by a `<ul>` list.
+    Html,
+}
+
+/// The heading of a section, such as 三画, or 其他 for surnames whose first
+/// character has no known stroke count.
+pub fn stroke_heading(strokes: Option<usize>) -> String {
+    match strokes {
+        Some(strokes) => format!("{}画", chinese_number(strokes)),
+        None => "其他".to_string(),
+    }
+}
+
+/// Writes `names` in sections headed by the stroke count of the first
+/// character of the surname, which is what stroke order sorts by first, so a
+/// compound surname such as 欧阳 is filed with 欧. A new section starts
+/// whenever the count changes from one name to the next. Fails with
+/// [`io::ErrorKind::InvalidInput`] under [`Collation::Pinyin`], which does
+/// not sort by strokes.
+pub(crate) fn write_grouped<W: Write>(
+    writer: &mut W,
+    sorter: &NameSorter,
+    names: &[String],
+    style: GroupStyle,
+) -> io::Result<()> {
+    if sorter.collation == Collation::Pinyin {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "grouped output is headed by stroke counts and cannot be used with pinyin order",
+        ));
+    }
+    let mut start = 0;
+    while start < names.len() {
+        let strokes = sorter.surname_strokes(&names[start]);
+        let len = names[start..]
+            .iter()
+            .take_while(|name| sorter.surname_strokes(name) == strokes)
+            .count();
+        let heading = stroke_heading(strokes);
+        let section = &names[start..start + len];
+
+        match style {
+            GroupStyle::Plain => {
+                if start > 0 {
+                    writeln!(writer)?;
+                }
+                writeln!(writer, "{}", heading)?;
+                for name in section {
+                    writeln!(writer, "{}", sorter.display_name(name))?;
+                }
+            }
+            GroupStyle::Markdown => {
+                if start > 0 {
+                    writeln!(writer)?;
+                }
+                writeln!(writer, "## {}\n", heading)?;
+                for name in section {
+                    writeln!(writer, "- {}", markdown_escape(&sorter.display_name(name)))?;
+                }
+            }
+            GroupStyle::Html => {
+                writeln!(writer, "<h2>{}</h2>\n<ul>", heading)?;
+                for name in section {
+                    writeln!(writer, "  <li>{}</li>", html_escape(&sorter.display_name(name)))?;
+                }
+                writeln!(writer, "</ul>")?;
+            }
+        }
+        start += len;
+    }
+    Ok(())
+}
+
+fn chinese_number(n: usize) -> String {
+    const DIGITS: [&str; 10] = ["零", "一", "二", "三", "四", "五", "六", "七", "八", "九"];
+    match (n / 10, n % 10) {
+        (0, ones) => DIGITS[ones].to_string(),
+        (1, 0) => "十".to_string(),
+        (1, ones) => format!("十{}", DIGITS[ones]),
+        (tens @ 2..=9, 0) => format!("{}十", DIGITS[tens]),
+        (tens @ 2..=9, ones) => format!("{}十{}", DIGITS[tens], DIGITS[ones]),
+        _ => n.to_string(),
+    }
+}
+
+fn markdown_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        if "\\`*_[]<>#|".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,617 @@
+mod collation;
+mod dict;
+mod error;
+mod external;
+mod group;
+mod normalize;
+mod output;
+mod pinyin;
+mod records;
+mod surnames;
+mod table;
+mod unknown;
+mod variants;
+#[cfg(feature = "xlsx")]
+mod xlsx;
+
+pub use collation::{Collation, TieBreak};
+pub use dict::{load_word_dict, StrokeDict};
+pub use error::SortError;
+pub use external::sort_external;
+pub use group::{stroke_heading, GroupStyle};
+pub use normalize::{Normalization, NormalizedName, NormalizedNames};
+pub use pinyin::{
+    load_pinyin_dict, load_surname_readings, read_pinyin_dict, read_surname_readings, PinyinDict, SurnameReadings,
+};
+pub use output::{write_names, Column, OutputFormat};
+pub use records::{
+    load_records, read_records, sort_records, write_records, write_records_output, FieldPath, RecordFormat,
+};
+pub use surnames::{
+    embedded_compound_surnames_set, load_compound_surnames_set, read_compound_surnames_set, SurnameTrie,
+};
+pub use table::{
+    load_table, read_table, write_table, write_table_output, CellKind, ColumnRef, KeyKind, Table, TableFormat,
+    TableKey,
+};
+pub use unknown::{UnknownChar, UnknownChars, UnknownPolicy};
+pub use variants::{load_variants, read_variants, VariantDuplicates, VariantMap};
+#[cfg(feature = "xlsx")]
+pub use xlsx::{load_xlsx, read_xlsx, write_xlsx, write_xlsx_output};
+
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter};
+use std::path::Path;
+
+
+/// Sorts Chinese names by the strokes of the surname, then of the given name.
+pub struct NameSorter {
+    word_dict: StrokeDict,
+    pinyin_dict: PinyinDict,
+    surname_readings: SurnameReadings,
+    surnames: SurnameTrie,
+    collation: Collation,
+    tie_break: TieBreak,
+    unknown_policy: UnknownPolicy,
+    surname_delimiter: Option<char>,
+    variants: VariantMap,
+    fold_variants: bool,
+    normalization: Normalization,
+}
+
+impl NameSorter {
+    pub fn new<D: Into<StrokeDict>, S: Into<SurnameTrie>>(word_dict: D, surnames: S) -> Self {
+        NameSorter {
+            word_dict: word_dict.into(),
+            pinyin_dict: PinyinDict::embedded(),
+            surname_readings: SurnameReadings::embedded(),
+            surnames: surnames.into(),
+            collation: Collation::default(),
+            tie_break: TieBreak::default(),
+            unknown_policy: UnknownPolicy::default(),
+            surname_delimiter: Some('|'),
+            variants: VariantMap::embedded(),
+            fold_variants: false,
+            normalization: Normalization::default(),
+        }
+    }
+
+    /// Replaces the embedded pinyin dictionary used by [`Collation::Pinyin`].
+    pub fn with_pinyin_dict(mut self, pinyin_dict: PinyinDict) -> Self {
+        self.pinyin_dict = pinyin_dict;
+        self
+    }
+
+    /// Replaces the embedded table of surname readings used by [`Collation::Pinyin`].
+    pub fn with_surname_readings(mut self, surname_readings: SurnameReadings) -> Self {
+        self.surname_readings = surname_readings;
+        self
+    }
+
+    pub fn with_collation(mut self, collation: Collation) -> Self {
+        self.collation = collation;
+        self
+    }
+
+    pub fn with_tie_break(mut self, tie_break: TieBreak) -> Self {
+        self.tie_break = tie_break;
+        self
+    }
+
+    /// A character that marks where the surname ends, so `司|马光` is split
+    /// into 司 and 马光 whatever the surname list says. Defaults to `|`,
+    /// `None` always uses the surname list.
+    pub fn with_surname_delimiter(mut self, delimiter: Option<char>) -> Self {
+        self.surname_delimiter = delimiter;
+        self
+    }
+
+    /// Replaces the embedded variant table of traditional characters and
+    /// their simplified forms, and of 异体字, used by
+    /// [`with_fold_variants`](Self::with_fold_variants) and
+    /// [`canonical_name`](Self::canonical_name).
+    pub fn with_variants(mut self, variants: VariantMap) -> Self {
+        self.variants = variants;
+        self
+    }
+
+    /// Looks characters up under their simplified form from the variant
+    /// table, so a traditional name such as 陳大文 sorts by the strokes of
+    /// 陈大文, among the simplified names, rather than by its own. Off by
+    /// default.
+    pub fn with_fold_variants(mut self, fold: bool) -> Self {
+        self.fold_variants = fold;
+        self
+    }
+
+    /// How characters are normalized before they are looked up, see
+    /// [`Normalization`]. Defaults to [`Normalization::Nfc`]; names are
+    /// printed as they were given either way.
+    pub fn with_normalization(mut self, normalization: Normalization) -> Self {
+        self.normalization = normalization;
+        self
+    }
+
+    pub fn with_unknown_policy(mut self, policy: UnknownPolicy) -> Self {
+        self.unknown_policy = policy;
+        self
+    }
+
+    pub fn from_files<P: AsRef<Path>, Q: AsRef<Path>>(dict_path: P, surnames_path: Q) -> io::Result<Self> {
+        Ok(NameSorter::new(
+            load_word_dict(dict_path)?,
+            load_compound_surnames_set(surnames_path)?,
+        ))
+    }
+
+    /// Sorts `names`, ordering ties as the [`TieBreak`] says. Each name's
+    /// [`sort_key`](Self::sort_key) is computed once up front rather than on
+    /// every comparison.
+    ///
+    /// Under [`UnknownPolicy::Error`] unknown characters are sorted last and
+    /// under [`TieBreak::Error`] ties keep their input order; use
+    /// [`try_sort`](Self::try_sort) to have either rejected instead.
+    pub fn sort(&self, names: &mut [String]) {
+        self.sort_by_name(names, String::as_str);
+    }
+
+    /// Like [`sort`](Self::sort), but sorts any items by the name `name`
+    /// returns for each, such as table rows by their name column.
+    pub fn sort_by_name<T, F>(&self, items: &mut [T], name: F)
+    where
+        F: Fn(&T) -> &str,
+    {
+        if self.tie_break == TieBreak::ReverseInputOrder {
+            items.reverse();
+        }
+        items.sort_by_cached_key(|item| self.sort_key(name(item)));
+    }
+
+    /// Like [`sort`](Self::sort), but computes the keys and sorts them on
+    /// rayon's thread pool. The result is always identical to `sort`.
+    #[cfg(feature = "parallel")]
+    pub fn par_sort(&self, names: &mut [String]) {
+        self.par_sort_by_name(names, String::as_str);
+    }
+
+    /// The parallel counterpart of [`sort_by_name`](Self::sort_by_name).
+    #[cfg(feature = "parallel")]
+    pub fn par_sort_by_name<T, F>(&self, items: &mut [T], name: F)
+    where
+        T: Send,
+        F: Fn(&T) -> &str + Sync,
+    {
+        use rayon::slice::ParallelSliceMut;
+
+        if self.tie_break == TieBreak::ReverseInputOrder {
+            items.reverse();
+        }
+        items.par_sort_by_cached_key(|item| self.sort_key(name(item)));
+    }
+
+    /// Like [`sort`](Self::sort), but fails under [`UnknownPolicy::Error`] if
+    /// there are unknown characters, leaving `names` untouched, and under
+    /// [`TieBreak::Error`] if two different names tie, leaving them sorted
+    /// with ties in input order.
+    pub fn try_sort(&self, names: &mut [String]) -> Result<(), SortError> {
+        self.try_sort_by_name(names, String::as_str)
+    }
+
+    /// The fallible counterpart of [`sort_by_name`](Self::sort_by_name).
+    pub fn try_sort_by_name<T, F>(&self, items: &mut [T], name: F) -> Result<(), SortError>
+    where
+        F: Fn(&T) -> &str,
+    {
+        self.check_unknown(items, &name)?;
+        self.sort_by_name(items, &name);
+        self.check_ties(items, &name)
+    }
+
+    /// Like [`try_sort`](Self::try_sort), but sorts with
+    /// [`par_sort`](Self::par_sort).
+    #[cfg(feature = "parallel")]
+    pub fn par_try_sort(&self, names: &mut [String]) -> Result<(), SortError> {
+        self.par_try_sort_by_name(names, String::as_str)
+    }
+
+    /// The parallel counterpart of [`try_sort_by_name`](Self::try_sort_by_name).
+    #[cfg(feature = "parallel")]
+    pub fn par_try_sort_by_name<T, F>(&self, items: &mut [T], name: F) -> Result<(), SortError>
+    where
+        T: Send,
+        F: Fn(&T) -> &str + Sync,
+    {
+        self.check_unknown(items, &name)?;
+        self.par_sort_by_name(items, &name);
+        self.check_ties(items, &name)
+    }
+
+    fn check_unknown<T, F: Fn(&T) -> &str>(&self, items: &[T], name: F) -> Result<(), SortError> {
+        if self.unknown_policy == UnknownPolicy::Error {
+            let names: Vec<&str> = items.iter().map(name).collect();
+            let unknown = self.unknown_chars(&names);
+            if !unknown.is_empty() {
+                return Err(UnknownChars(unknown, self.collation).into());
+            }
+        }
+        Ok(())
+    }
+
+    fn check_ties<T, F: Fn(&T) -> &str>(&self, items: &[T], name: F) -> Result<(), SortError> {
+        if self.tie_break == TieBreak::Error {
+            if let Some(pair) = items.windows(2).find(|pair| {
+                let (a, b) = (name(&pair[0]), name(&pair[1]));
+                a != b && self.compare(a, b) == Ordering::Equal
+            }) {
+                return Err(SortError::Tie(name(&pair[0]).to_string(), name(&pair[1]).to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Compares two names under the collation, without any tie-break.
+    pub fn compare(&self, a: &str, b: &str) -> Ordering {
+        let (surname_a, given_a) = self.split_parts(a);
+        let (surname_b, given_b) = self.split_parts(b);
+
+        match self.char_ranks(surname_a, true).cmp(self.char_ranks(surname_b, true)) {
+            Ordering::Equal => {
+                self.char_ranks(given_a, false).cmp(self.char_ranks(given_b, false))
+            }
+            ord => ord,
+        }
+    }
+
+    /// A byte string whose order matches [`compare`](Self::compare): for any
+    /// two names, `sort_key(a).cmp(&sort_key(b)) == compare(a, b)`. It is the
+    /// surname's key, a 0 separator, then the given name's key and another 0.
+    /// Under [`TieBreak::CodePoint`] the name itself follows, so the keys
+    /// order ties too. Keys depend on the dictionaries and settings of this
+    /// sorter and should be recomputed when those change.
+    pub fn sort_key(&self, name: &str) -> Vec<u8> {
+        let (surname, given_name) = self.split_parts(name);
+        let mut key = Vec::with_capacity(16 * name.len());
+        for rank in self.char_ranks(surname, true) {
+            rank.encode(&mut key);
+        }
+        key.push(0);
+        for rank in self.char_ranks(given_name, false) {
+            rank.encode(&mut key);
+        }
+        key.push(0);
+        if self.tie_break == TieBreak::CodePoint {
+            key.extend_from_slice(name.as_bytes());
+        }
+        key
+    }
+
+    /// [`sort_key`](Self::sort_key) as lowercase hexadecimal, which sorts the
+    /// same way as text.
+    pub fn sort_key_hex(&self, name: &str) -> String {
+        self.sort_key(name).iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    /// Every character of `names` missing from the dictionary the collation
+    /// sorts by, in order of first appearance.
+    pub fn unknown_chars<S: AsRef<str>>(&self, names: &[S]) -> Vec<UnknownChar> {
+        let mut unknown: Vec<UnknownChar> = Vec::new();
+        let mut index: HashMap<char, usize> = HashMap::new();
+        for name in names.iter().map(AsRef::as_ref) {
+            for c in self.display_name(name).chars().filter(|&c| !self.is_known(c)) {
+                let i = *index.entry(c).or_insert_with(|| {
+                    unknown.push(UnknownChar { ch: c, names: Vec::new() });
+                    unknown.len() - 1
+                });
+                if unknown[i].names.last().map(String::as_str) != Some(name) {
+                    unknown[i].names.push(name.to_string());
+                }
+            }
+        }
+        unknown
+    }
+
+    /// `name` as printed, normalized and with every character of the variant
+    /// table replaced by the form it folds to, so 張偉 and 张伟 have the same
+    /// canonical name. This does not depend on
+    /// [`with_fold_variants`](Self::with_fold_variants).
+    pub fn canonical_name(&self, name: &str) -> String {
+        self.display_name(name)
+            .chars()
+            .map(|c| {
+                let c = self.normalization.normalize_char(c);
+                self.variants.get(c).unwrap_or(c)
+            })
+            .collect()
+    }
+
+    /// The names in `names` that are looked up as a different text once
+    /// normalized, such as ⾦城武 with the Kangxi radical ⾦ for 金城武, in
+    /// order of first appearance.
+    pub fn normalized_names<S: AsRef<str>>(&self, names: &[S]) -> Vec<NormalizedName> {
+        let mut found: Vec<NormalizedName> = Vec::new();
+        for name in names.iter().map(AsRef::as_ref) {
+            let name = self.display_name(name);
+            let normalized: String = name.chars().map(|c| self.normalization.normalize_char(c)).collect();
+            if normalized != name && !found.iter().any(|other| other.name == name) {
+                found.push(NormalizedName { name: name.into_owned(), normalized });
+            }
+        }
+        found
+    }
+
+    /// Groups of different names in `names` that share a
+    /// [`canonical_name`](Self::canonical_name), such as 张伟 and 張偉, in
+    /// order of first appearance.
+    pub fn variant_duplicates<S: AsRef<str>>(&self, names: &[S]) -> Vec<Vec<String>> {
+        let mut groups: Vec<Vec<String>> = Vec::new();
+        let mut index: HashMap<String, usize> = HashMap::new();
+        for name in names.iter().map(AsRef::as_ref) {
+            let name = self.display_name(name);
+            let i = *index.entry(self.canonical_name(&name)).or_insert_with(|| {
+                groups.push(Vec::new());
+                groups.len() - 1
+            });
+            if !groups[i].iter().any(|other| *other == name) {
+                groups[i].push(name.into_owned());
+            }
+        }
+        groups.retain(|names| names.len() > 1);
+        groups
+    }
+
+    /// Splits a name into `(surname, given_name)`. The surname ends at the
+    /// surname delimiter if the name contains one, otherwise it is the longest
+    /// entry of the surname list the name starts with, or else its first character.
+    pub fn split(&self, name: &str) -> (String, String) {
+        let (surname, given_name) = self.split_parts(name);
+        (surname.to_string(), given_name.to_string())
+    }
+
+    fn split_parts<'a>(&self, name: &'a str) -> (&'a str, &'a str) {
+        if let Some(parts) = self.surname_delimiter.and_then(|d| name.split_once(d)) {
+            return parts;
+        }
+        let folded = self.fold(name);
+        let at = match self.surnames.longest_prefix(&folded) {
+            Some(at) if matches!(folded, Cow::Borrowed(_)) => at,
+            // Folding maps character to character, so the prefix ends after
+            // as many characters of `name`.
+            Some(at) => {
+                let len = folded[..at].chars().count();
+                name.char_indices().nth(len).map_or(name.len(), |(i, _)| i)
+            }
+            None => name.chars().next().map_or(0, char::len_utf8),
+        };
+        name.split_at(at)
+    }
+
+    /// The name as it should be printed, without the surname delimiter.
+    pub fn display_name<'a>(&self, name: &'a str) -> Cow<'a, str> {
+        match self.surname_delimiter {
+            Some(delimiter) if name.contains(delimiter) => Cow::Owned(name.replacen(delimiter, "", 1)),
+            _ => Cow::Borrowed(name),
+        }
+    }
+
+    /// Total number of strokes of the characters in `text`. Unknown characters
+    /// count as zero unless the policy is [`UnknownPolicy::Strokes`].
+    pub fn stroke_count(&self, text: &str) -> usize {
+        let unknown = match self.unknown_policy {
+            UnknownPolicy::Strokes(strokes) => strokes,
+            _ => 0,
+        };
+        self.stroke_orders(text)
+            .iter()
+            .map(|order| order.map_or(unknown, str::len))
+            .sum()
+    }
+
+    /// Stroke count of the first character of the surname of `name`, which
+    /// [`OutputFormat::Grouped`] uses to head its sections. `None` if the
+    /// character is missing from the dictionary and the unknown policy gives
+    /// it no stroke count.
+    pub fn surname_strokes(&self, name: &str) -> Option<usize> {
+        let first = self.split_parts(name).0.chars().next()?;
+        match (self.word_dict.get(self.fold_char(first)), self.unknown_policy) {
+            (Some(order), _) => Some(order.len()),
+            (None, UnknownPolicy::Strokes(strokes)) => Some(strokes),
+            (None, _) => None,
+        }
+    }
+
+    /// The stroke sequence of each character in `text`, `None` for characters
+    /// missing from the dictionary.
+    pub fn stroke_orders(&self, text: &str) -> Vec<Option<&str>> {
+        text.chars().map(|c| self.word_dict.get(self.fold_char(c))).collect()
+    }
+
+    /// The pinyin syllable and tone of each character in `name`, `None` for
+    /// characters missing from the pinyin dictionary. A surname listed in the
+    /// surname readings takes its reading from there.
+    pub fn pinyin(&self, name: &str) -> Vec<Option<(&str, u8)>> {
+        let (surname, given_name) = self.split_parts(name);
+        let surname_readings = self.surname_readings.get(&self.fold(surname)).unwrap_or_default();
+        let surname = surname.chars().enumerate().map(|(i, c)| match surname_readings.get(i) {
+            Some((syllable, tone)) => Some((syllable.as_str(), *tone)),
+            None => self.pinyin_dict.get(self.fold_char(c)),
+        });
+        surname.chain(given_name.chars().map(|c| self.pinyin_dict.get(self.fold_char(c)))).collect()
+    }
+
+    /// `text` as it is looked up: normalized, and with variants folded if
+    /// [`with_fold_variants`](Self::with_fold_variants) is on.
+    fn fold<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        if text.chars().any(|c| self.fold_char(c) != c) {
+            Cow::Owned(text.chars().map(|c| self.fold_char(c)).collect())
+        } else {
+            Cow::Borrowed(text)
+        }
+    }
+
+    fn fold_char(&self, c: char) -> char {
+        let c = self.normalization.normalize_char(c);
+        if self.fold_variants {
+            self.variants.get(c).unwrap_or(c)
+        } else {
+            c
+        }
+    }
+
+    fn is_known(&self, c: char) -> bool {
+        let c = self.fold_char(c);
+        match self.collation {
+            Collation::Strokes | Collation::Gb13418 | Collation::StrokeCountOnly => self.word_dict.get(c).is_some(),
+            Collation::Pinyin => self.pinyin_dict.get(c).is_some(),
+        }
+    }
+
+    /// The ranks of the characters of one part of a name. Comparing two of
+    /// these sequences orders the parts, a part that runs out first sorting
+    /// before the longer one.
+    fn char_ranks<'a>(&'a self, text: &'a str, surname: bool) -> impl Iterator<Item = CharRank<'a>> + 'a {
+        let readings = match self.collation {
+            Collation::Pinyin if surname => self.surname_readings.get(&self.fold(text)),
+            _ => None,
+        };
+        // A table entry with fewer readings than characters leaves the rest
+        // to the everyday readings.
+        text.chars().enumerate().map(move |(i, c)| match readings.and_then(|readings| readings.get(i)) {
+            Some((pinyin, tone)) => {
+                let c = self.fold_char(c);
+                CharRank {
+                    pinyin,
+                    tone: *tone,
+                    ..CharRank::strokes(self.word_dict.get(c).unwrap_or_default(), Some(c))
+                }
+            }
+            None => self.char_rank(c),
+        })
+    }
+
+    /// Where a character sorts under the collation. Unknown characters are
+    /// placed by the policy and, except under [`Collation::StrokeCountOnly`],
+    /// ordered among themselves by code point.
+    fn char_rank(&self, c: char) -> CharRank<'_> {
+        let c = self.fold_char(c);
+        let order = self.word_dict.get(c);
+        let known = match self.collation {
+            Collation::Strokes => order.map(|order| CharRank::strokes(order, None)),
+            Collation::Gb13418 => order.map(|order| CharRank::strokes(order, Some(c))),
+            Collation::StrokeCountOnly => order.map(|order| CharRank {
+                order: "",
+                ..CharRank::strokes(order, None)
+            }),
+            Collation::Pinyin => self.pinyin_dict.get(c).map(|(pinyin, tone)| CharRank {
+                pinyin,
+                tone,
+                ..CharRank::strokes(order.unwrap_or_default(), Some(c))
+            }),
+        };
+        let mut rank = match (known, self.unknown_policy) {
+            (Some(rank), _) => return rank,
+            (None, UnknownPolicy::First) => CharRank::unknown(0, c),
+            (None, UnknownPolicy::Strokes(strokes)) if self.collation != Collation::Pinyin => CharRank {
+                strokes,
+                order: "6",
+                ..CharRank::unknown(1, c)
+            },
+            (None, _) => CharRank::unknown(2, c),
+        };
+        if self.collation == Collation::StrokeCountOnly {
+            rank.order = "";
+            rank.code_point = None;
+        }
+        rank
+    }
+}
+
+/// Compared field by field: class 0 holds unknown characters sorted first,
+/// 1 the known ones, 2 unknown characters sorted last.
+///
+/// [`encode`](Self::encode) writes the same fields so that comparing the
+/// bytes gives the same order: each variable-length field is closed by a
+/// 0 byte that the field itself never contains, and a whole part of a name
+/// is closed by a 0 byte where the next rank would start with its class,
+/// which is at least 1.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct CharRank<'a> {
+    class: u8,
+    pinyin: &'a str,
+    tone: u8,
+    strokes: usize,
+    order: &'a str,
+    code_point: Option<char>,
+}
+
+impl<'a> CharRank<'a> {
+    fn strokes(order: &'a str, code_point: Option<char>) -> Self {
+        CharRank {
+            class: 1,
+            pinyin: "",
+            tone: 0,
+            strokes: order.len(),
+            order,
+            code_point,
+        }
+    }
+
+    fn unknown(class: u8, c: char) -> Self {
+        CharRank {
+            class,
+            code_point: Some(c),
+            ..CharRank::strokes("", None)
+        }
+    }
+
+    fn encode(&self, key: &mut Vec<u8>) {
+        key.push(self.class + 1);
+        key.extend_from_slice(self.pinyin.as_bytes());
+        key.push(0);
+        key.push(self.tone);
+        key.extend_from_slice(&(self.strokes.min(u16::MAX as usize) as u16).to_be_bytes());
+        key.extend_from_slice(self.order.as_bytes());
+        key.push(0);
+        match self.code_point {
+            Some(c) => {
+                key.push(1);
+                key.extend_from_slice(&(c as u32).to_be_bytes()[1..]);
+            }
+            None => key.push(0),
+        }
+    }
+}
+
+/// Uses the stroke dictionary and compound surname list compiled into the crate.
+impl Default for NameSorter {
+    fn default() -> Self {
+        NameSorter::new(StrokeDict::embedded(), embedded_compound_surnames_set())
+    }
+}
+
+pub fn load_names<P: AsRef<Path>>(path: P) -> io::Result<Vec<String>> {
+    read_names(BufReader::new(File::open(path)?))
+}
+
+/// Reads one name per line, skipping blank lines.
+pub fn read_names<R: BufRead>(reader: R) -> io::Result<Vec<String>> {
+    reader
+        .lines()
+        .map(|line| Ok(line?.trim().to_string()))
+        .filter(|s| match s {
+            Ok(s) => !s.is_empty(),
+            _ => true
+        })
+        .collect()
+}
+
+pub fn write_output<P: AsRef<Path>>(
+    path: P,
+    sorter: &NameSorter,
+    names: &[String],
+    format: &OutputFormat,
+) -> io::Result<()> {
+    write_names(BufWriter::new(File::create(path)?), sorter, names, format)
+}
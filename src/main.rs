@@ -1,117 +1,265 @@
-use serde::Deserialize;
-use std::collections::{HashMap, HashSet};
+mod cli;
+
+use cli::{Args, Command, RecordArgs, TableArgs, TableFile};
+#[cfg(feature = "xlsx")]
+use sort_chinese_name::{load_xlsx, read_xlsx, write_xlsx, write_xlsx_output};
+use sort_chinese_name::{
+    embedded_compound_surnames_set, load_compound_surnames_set, load_names, load_pinyin_dict, load_records,
+    load_surname_readings, load_table, load_variants, load_word_dict, read_names, read_records, read_table, sort_external,
+    sort_records, write_names, write_output, write_records, write_records_output, write_table, write_table_output,
+    NameSorter, NormalizedName, NormalizedNames, PinyinDict, StrokeDict, SurnameReadings, UnknownChar, UnknownChars, UnknownPolicy, VariantDuplicates,
+    VariantMap,
+};
 use std::fs::File;
-use std::io::{self, BufRead, BufReader, Write};
+use std::io::{self, BufRead, BufReader, BufWriter};
 use std::path::Path;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let args = match cli::parse(std::env::args().skip(1)) {
+        Ok(Command::Run(args)) => args,
+        Ok(Command::Help) => {
+            print!("{}", cli::USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Ok(Command::Version) => {
+            println!("SortChineseName {}", env!("CARGO_PKG_VERSION"));
+            return ExitCode::SUCCESS;
+        }
+        Err(err) => {
+            eprintln!("error: {}\n\nFor more information, try `--help`.", err);
+            return ExitCode::from(2);
+        }
+    };
 
-#[derive(Deserialize)]
-struct JsonData {
-    word: String,
-    order: String,
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
 }
 
-fn main() -> io::Result<()> {
-    let word_dict = load_word_dict("data.json")?;
+fn run(args: &Args) -> io::Result<()> {
+    let word_dict = match &args.dict {
+        Some(path) => load_word_dict(path).map_err(|err| with_path(err, path))?,
+        None => StrokeDict::embedded(),
+    };
+    let compound_surnames_set = match &args.surnames {
+        Some(path) => load_compound_surnames_set(path).map_err(|err| with_path(err, path))?,
+        None => embedded_compound_surnames_set(),
+    };
+    let pinyin_dict = match &args.pinyin {
+        Some(path) => load_pinyin_dict(path).map_err(|err| with_path(err, path))?,
+        None => PinyinDict::embedded(),
+    };
+    let surname_readings = match &args.surname_pinyin {
+        Some(path) => load_surname_readings(path).map_err(|err| with_path(err, path))?,
+        None => SurnameReadings::embedded(),
+    };
+    let variants = match &args.variants {
+        Some(path) => load_variants(path).map_err(|err| with_path(err, path))?,
+        None => VariantMap::embedded(),
+    };
+    let sorter = NameSorter::new(word_dict, compound_surnames_set)
+        .with_pinyin_dict(pinyin_dict)
+        .with_surname_readings(surname_readings)
+        .with_variants(variants)
+        .with_fold_variants(args.fold_variants)
+        .with_normalization(args.normalization)
+        .with_surname_delimiter(args.surname_delimiter)
+        .with_collation(args.order)
+        .with_tie_break(args.ties)
+        .with_unknown_policy(args.unknown);
 
-    let compound_surnames_set = load_compound_surnames_set("compound_surnames.txt")?;
+    if let Some(table) = &args.table {
+        return sort_table(args, &sorter, table);
+    }
+    if let Some(records) = &args.records {
+        return sort_json(args, &sorter, records);
+    }
+    if let Some(run_size) = args.run_size {
+        return sort_in_runs(args, &sorter, run_size);
+    }
 
-    let mut names = load_names("names.txt")?;
-    names.reverse(); 
+    let mut names = if is_stdio(&args.input) {
+        read_names(io::stdin().lock())?
+    } else {
+        load_names(&args.input).map_err(|err| with_path(err, &args.input))?
+    };
 
-    names.sort_by(|a, b| compare_names(a, b, &compound_surnames_set, &word_dict));
-    
-    write_output("out.txt", &names)?;
-    
-    Ok(())
-}
+    if args.report_unknown || args.unknown != UnknownPolicy::Error {
+        report_unknown(args, sorter.unknown_chars(&names));
+    }
+    if args.report_variants {
+        report_variants(sorter.variant_duplicates(&names));
+    }
+    if args.report_normalized {
+        report_normalized(sorter.normalized_names(&names));
+    }
 
-fn load_word_dict<P: AsRef<Path>>(path: P) -> io::Result<HashMap<String, String>> {
-    let file = File::open(path)?;
-    let data: Vec<JsonData> = serde_json::from_reader(file)?;
-    Ok(data.into_iter()
-        .map(|d| (d.word, d.order))
-        .collect())
-}
+    #[cfg(feature = "parallel")]
+    let sorted = sorter.par_try_sort(&mut names);
+    #[cfg(not(feature = "parallel"))]
+    let sorted = sorter.try_sort(&mut names);
+    sorted.map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
 
-fn load_compound_surnames_set<P: AsRef<Path>>(path: P) -> io::Result<HashSet<String>> {
-    BufReader::new(File::open(path)?)
-        .lines()
-        .map(|line| Ok(line?.trim().to_string()))
-        .collect()
+    if is_stdio(&args.output) {
+        write_names(BufWriter::new(io::stdout().lock()), &sorter, &names, &args.format)
+    } else {
+        write_output(&args.output, &sorter, &names, &args.format).map_err(|err| with_path(err, &args.output))
+    }
 }
 
-fn load_names<P: AsRef<Path>>(path: P) -> io::Result<Vec<String>> {
-    BufReader::new(File::open(path)?)
-        .lines()
-        .map(|line| Ok(line?.trim().to_string()))
-        .filter(|s| match s {
-            Ok(s) => !s.is_empty(),
-            _ => true
-        })
-        .collect()
-}
+fn sort_table(args: &Args, sorter: &NameSorter, table_args: &TableArgs) -> io::Result<()> {
+    let mut table = match table_args.input {
+        TableFile::Text(format) if is_stdio(&args.input) => read_table(io::stdin().lock(), format, table_args.has_header)?,
+        TableFile::Text(format) => {
+            load_table(&args.input, format, table_args.has_header).map_err(|err| with_path(err, &args.input))?
+        }
+        #[cfg(feature = "xlsx")]
+        TableFile::Xlsx if is_stdio(&args.input) => {
+            let mut bytes = Vec::new();
+            io::copy(&mut io::stdin().lock(), &mut bytes)?;
+            read_xlsx(io::Cursor::new(bytes), table_args.sheet.as_deref(), table_args.has_header)?
+        }
+        #[cfg(feature = "xlsx")]
+        TableFile::Xlsx => load_xlsx(&args.input, table_args.sheet.as_deref(), table_args.has_header)
+            .map_err(|err| with_path(err, &args.input))?,
+        #[cfg(not(feature = "xlsx"))]
+        TableFile::Xlsx => unreachable!("xlsx is rejected by cli::parse without the xlsx feature"),
+    };
+    let index = table.column_index(&table_args.name_column).map_err(|err| with_path(err, &args.input))?;
 
-fn split_name(name: &str, compound_surnames_set: &HashSet<String>) -> (String, String) {
-    if name.chars().count() >= 2 {
-        let mut chars = name.chars();
-        let first_char = chars.next().unwrap();
-        let second_char = chars.next().unwrap();
-        let possible_compound_surnames = format!("{}{}", first_char, second_char);
-        if compound_surnames_set.contains(&possible_compound_surnames) {
-            return (possible_compound_surnames, chars.as_str().to_string());
+    if args.report_unknown || args.unknown != UnknownPolicy::Error {
+        report_unknown(args, sorter.unknown_chars(&table.names(index)));
+    }
+    if args.report_variants {
+        report_variants(sorter.variant_duplicates(&table.names(index)));
+    }
+    if args.report_normalized {
+        report_normalized(sorter.normalized_names(&table.names(index)));
+    }
+
+    if table_args.sort_by.is_empty() {
+        table
+            .sort(sorter, index)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    } else {
+        table.sort_by_keys(sorter, &table_args.sort_by).map_err(|err| with_path(err, &args.input))?;
+    }
+    table.add_columns(sorter, index, &table_args.add_columns);
+
+    match table_args.output {
+        TableFile::Text(format) if is_stdio(&args.output) => {
+            write_table(BufWriter::new(io::stdout().lock()), sorter, &table, index, format)
         }
+        TableFile::Text(format) => write_table_output(&args.output, sorter, &table, index, format)
+            .map_err(|err| with_path(err, &args.output)),
+        #[cfg(feature = "xlsx")]
+        TableFile::Xlsx if is_stdio(&args.output) => write_xlsx(io::stdout().lock(), sorter, &table, index),
+        #[cfg(feature = "xlsx")]
+        TableFile::Xlsx => {
+            write_xlsx_output(&args.output, sorter, &table, index).map_err(|err| with_path(err, &args.output))
+        }
+        #[cfg(not(feature = "xlsx"))]
+        TableFile::Xlsx => unreachable!("xlsx is rejected by cli::parse without the xlsx feature"),
     }
-    let mut chars = name.chars();
-    let surname = chars.next().unwrap().to_string();
-    let given_name = chars.as_str().to_string();
-    (surname, given_name)
 }
 
-fn compare_chars(a: &str, b: &str, dict: &HashMap<String, String>) -> std::cmp::Ordering {
-    for (c1, c2) in a.chars().zip(b.chars()) {
-        let c1_str = c1.to_string();
-        let c2_str = c2.to_string();
+fn sort_json(args: &Args, sorter: &NameSorter, record_args: &RecordArgs) -> io::Result<()> {
+    let mut records = if is_stdio(&args.input) {
+        read_records(io::stdin().lock(), record_args.input)?
+    } else {
+        load_records(&args.input, record_args.input).map_err(|err| with_path(err, &args.input))?
+    };
 
-        let default_code = "66666".to_string();
+    let names: Vec<&str> = records.iter().map(|record| record_args.name_field.name(record)).collect();
+    if args.report_unknown || args.unknown != UnknownPolicy::Error {
+        report_unknown(args, sorter.unknown_chars(&names));
+    }
+    if args.report_variants {
+        report_variants(sorter.variant_duplicates(&names));
+    }
+    if args.report_normalized {
+        report_normalized(sorter.normalized_names(&names));
+    }
 
-        let code1 = dict.get(&c1_str).unwrap_or(&default_code);
-        let code2 = dict.get(&c2_str).unwrap_or(&default_code);
+    sort_records(sorter, &mut records, &record_args.name_field)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
 
-        match code1.len().cmp(&code2.len()) {
-            std::cmp::Ordering::Equal => {
-                match code1.cmp(code2) {
-                    std::cmp::Ordering::Equal => continue,
-                    ord => return ord,
-                }
-            }
-            ord => return ord,
-        }
+    if is_stdio(&args.output) {
+        write_records(
+            BufWriter::new(io::stdout().lock()),
+            sorter,
+            &records,
+            &record_args.name_field,
+            record_args.output,
+        )
+    } else {
+        write_records_output(&args.output, sorter, &records, &record_args.name_field, record_args.output)
+            .map_err(|err| with_path(err, &args.output))
+    }
+}
+
+fn sort_in_runs(args: &Args, sorter: &NameSorter, run_size: usize) -> io::Result<()> {
+    let reader: Box<dyn BufRead> = if is_stdio(&args.input) {
+        Box::new(io::stdin().lock())
+    } else {
+        Box::new(BufReader::new(File::open(&args.input).map_err(|err| with_path(err, &args.input))?))
+    };
+    if is_stdio(&args.output) {
+        let unknown = sort_external(sorter, reader, BufWriter::new(io::stdout().lock()), run_size)?;
+        report_unknown(args, unknown);
+        return Ok(());
     }
 
-    a.len().cmp(&b.len())
+    // A tie under `--ties error` is only found while the runs are merged, so
+    // the names go to a temporary file next to the output, which replaces
+    // the output only once the whole merge has succeeded.
+    let dir = match args.output.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let output = tempfile::NamedTempFile::new_in(dir).map_err(|err| with_path(err, &args.output))?;
+    let unknown = sort_external(sorter, reader, BufWriter::new(output.as_file()), run_size)?;
+    output.persist(&args.output).map_err(|err| with_path(err.error, &args.output))?;
+    report_unknown(args, unknown);
+    Ok(())
 }
 
-fn compare_names(
-    a: &str,
-    b: &str,
-    compound_surnames_set: &HashSet<String>,
-    word_dict: &HashMap<String, String>,
-) -> std::cmp::Ordering {
-    let (surname_a, given_a) = split_name(a, compound_surnames_set);
-    let (surname_b, given_b) = split_name(b, compound_surnames_set);
-    
-    match compare_chars(&surname_a, &surname_b, word_dict) {
-        std::cmp::Ordering::Equal => {
-            compare_chars(&given_a, &given_b, word_dict)
-        }
-        ord => ord,
+fn report_unknown(args: &Args, unknown: Vec<UnknownChar>) {
+    if unknown.is_empty() {
+        return;
+    }
+    if args.report_unknown {
+        eprintln!("{}", UnknownChars(unknown, args.order));
+    } else if args.unknown != UnknownPolicy::Error {
+        eprintln!(
+            "warning: {} character(s) not in the {}, use --report-unknown to list them",
+            unknown.len(),
+            args.order.dictionary()
+        );
     }
 }
 
-fn write_output<P: AsRef<Path>>(path: P, names: &[String]) -> io::Result<()> {
-    let mut file = File::create(path)?;
-    for name in names {
-        write!(file, "{} ", name)?;
+fn report_variants(duplicates: Vec<Vec<String>>) {
+    if !duplicates.is_empty() {
+        eprintln!("{}", VariantDuplicates(duplicates));
     }
-    Ok(())
+}
+
+fn report_normalized(names: Vec<NormalizedName>) {
+    if !names.is_empty() {
+        eprintln!("{}", NormalizedNames(names));
+    }
+}
+
+fn is_stdio(path: &Path) -> bool {
+    path.as_os_str() == "-"
+}
+
+fn with_path(err: io::Error, path: &Path) -> io::Error {
+    io::Error::new(err.kind(), format!("{}: {}", path.display(), err))
 }
@@ -0,0 +1,116 @@
+use std::fmt;
+use std::str::FromStr;
+use unicode_normalization::UnicodeNormalization;
+
+/// How each character of a name is normalized before it is looked up, so
+/// characters that look the same as a dictionary entry, as pasted from PDFs,
+/// sort as that entry.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Normalization {
+    /// Look characters up as they are.
+    None,
+    /// NFC, which maps CJK Compatibility Ideographs such as U+F9B5 to the
+    /// unified ideograph 例, plus a mapping of radicals to the ideograph they
+    /// stand for: Kangxi Radicals such as ⾦ U+2FA6 to 金 by their NFKC form,
+    /// and the CJK Radicals Supplement such as ⻄ U+2EC4 to 西 by a table
+    /// built from Unicode's EquivalentUnifiedIdeograph.txt.
+    #[default]
+    Nfc,
+    /// NFKC, which also maps fullwidth and other compatibility characters.
+    Nfkc,
+}
+
+impl Normalization {
+    /// `c` normalized on its own. Characters that would not normalize to a
+    /// single character are left as they are.
+    pub fn normalize_char(self, c: char) -> char {
+        if self == Normalization::None || c.is_ascii() {
+            return c;
+        }
+        if let Ok(i) = SUPPLEMENT_RADICALS.binary_search_by_key(&c, |&(radical, _)| radical) {
+            return SUPPLEMENT_RADICALS[i].1;
+        }
+        let normalized = match self {
+            Normalization::Nfc if is_kangxi_radical(c) => single(c.nfkc()),
+            Normalization::Nfc => single(c.nfc()),
+            _ => single(c.nfkc()),
+        };
+        normalized.unwrap_or(c)
+    }
+}
+
+fn is_kangxi_radical(c: char) -> bool {
+    matches!(c, '\u{2F00}'..='\u{2FDF}')
+}
+
+/// CJK Radicals Supplement forms and the unified ideograph each stands for,
+/// following Unicode's EquivalentUnifiedIdeograph.txt, in code point order.
+/// Only U+2E9F and U+2EF3 have an NFKC form; forms with no single equivalent,
+/// such as ⺀ U+2E80, are left out.
+const SUPPLEMENT_RADICALS: &[(char, char)] = &[
+    ('\u{2E81}', '厂'), ('\u{2E84}', '乙'), ('\u{2E85}', '亻'), ('\u{2E86}', '冂'), ('\u{2E8A}', '卜'), ('\u{2E8C}', '小'),
+    ('\u{2E8D}', '小'), ('\u{2E8E}', '兀'), ('\u{2E8F}', '尣'), ('\u{2E90}', '尢'), ('\u{2E92}', '巳'), ('\u{2E93}', '幺'),
+    ('\u{2E94}', '彑'), ('\u{2E96}', '忄'), ('\u{2E98}', '扌'), ('\u{2E99}', '攵'), ('\u{2E9B}', '旡'), ('\u{2E9D}', '月'),
+    ('\u{2E9E}', '歺'), ('\u{2E9F}', '母'), ('\u{2EA0}', '民'), ('\u{2EA1}', '氵'), ('\u{2EA2}', '氺'), ('\u{2EA3}', '灬'),
+    ('\u{2EA4}', '爫'), ('\u{2EA5}', '爫'), ('\u{2EA6}', '丬'), ('\u{2EA7}', '牛'), ('\u{2EA8}', '犭'), ('\u{2EA9}', '王'),
+    ('\u{2EAA}', '疋'), ('\u{2EAC}', '示'), ('\u{2EAD}', '礻'), ('\u{2EAE}', '𥫗'), ('\u{2EAF}', '糸'), ('\u{2EB0}', '纟'),
+    ('\u{2EB2}', '罒'), ('\u{2EB9}', '耂'), ('\u{2EBB}', '聿'), ('\u{2EBE}', '艹'), ('\u{2EBF}', '艹'), ('\u{2EC0}', '艹'),
+    ('\u{2EC1}', '虎'), ('\u{2EC2}', '衤'), ('\u{2EC3}', '覀'), ('\u{2EC4}', '西'), ('\u{2EC5}', '见'), ('\u{2EC6}', '角'),
+    ('\u{2EC8}', '讠'), ('\u{2EC9}', '贝'), ('\u{2ECA}', '𧾷'), ('\u{2ECB}', '车'), ('\u{2ECC}', '辶'), ('\u{2ECD}', '辶'),
+    ('\u{2ECE}', '辶'), ('\u{2ECF}', '阝'), ('\u{2ED0}', '钅'), ('\u{2ED1}', '長'), ('\u{2ED2}', '镸'), ('\u{2ED3}', '长'),
+    ('\u{2ED4}', '门'), ('\u{2ED6}', '阝'), ('\u{2ED8}', '青'), ('\u{2ED9}', '韦'), ('\u{2EDA}', '页'), ('\u{2EDB}', '风'),
+    ('\u{2EDC}', '飞'), ('\u{2EDD}', '食'), ('\u{2EDF}', '飠'), ('\u{2EE0}', '饣'), ('\u{2EE2}', '马'), ('\u{2EE3}', '骨'),
+    ('\u{2EE4}', '鬼'), ('\u{2EE5}', '鱼'), ('\u{2EE6}', '鸟'), ('\u{2EE7}', '卤'), ('\u{2EE8}', '麦'), ('\u{2EE9}', '黄'),
+    ('\u{2EEA}', '黾'), ('\u{2EEB}', '斉'), ('\u{2EEC}', '齐'), ('\u{2EED}', '歯'), ('\u{2EEE}', '齿'), ('\u{2EEF}', '竜'),
+    ('\u{2EF0}', '龙'), ('\u{2EF2}', '亀'), ('\u{2EF3}', '龟'),
+];
+
+fn single<I: Iterator<Item = char>>(mut chars: I) -> Option<char> {
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ => None,
+    }
+}
+
+impl FromStr for Normalization {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Normalization::None),
+            "nfc" => Ok(Normalization::Nfc),
+            "nfkc" => Ok(Normalization::Nfkc),
+            _ => Err(format!("invalid normalization `{}`, expected none, nfc or nfkc", s)),
+        }
+    }
+}
+
+/// A name that normalization looks up as a different text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NormalizedName {
+    pub name: String,
+    pub normalized: String,
+}
+
+/// The names found by
+/// [`NameSorter::normalized_names`](crate::NameSorter::normalized_names),
+/// listed with the code points that changed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NormalizedNames(pub Vec<NormalizedName>);
+
+impl fmt::Display for NormalizedNames {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} name(s) changed by normalization:", self.0.len())?;
+        for name in &self.0 {
+            let changes: Vec<String> = name
+                .name
+                .chars()
+                .zip(name.normalized.chars())
+                .filter(|(from, to)| from != to)
+                .map(|(from, to)| format!("U+{:04X} → U+{:04X}", from as u32, to as u32))
+                .collect();
+            write!(f, "\n  {} → {} ({})", name.name, name.normalized, changes.join(", "))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,197 @@
+use crate::group::{self, GroupStyle};
+use crate::NameSorter;
+use serde_json::{Map, Value};
+use std::fmt;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+/// How sorted names are written out.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// One name per line.
+    #[default]
+    Lines,
+    /// All names on one line, joined by the given separator.
+    Separated(String),
+    /// Comma-separated values with a header row.
+    Csv(Vec<Column>),
+    /// Tab-separated values with a header row.
+    Tsv(Vec<Column>),
+    /// A JSON array of names, or of objects when more than the name column is asked for.
+    Json(Vec<Column>),
+    /// Sections headed by surname stroke count, such as 三画 and 四画.
+    Grouped(GroupStyle),
+    /// `name<TAB>key` lines, the key being the hexadecimal sort key.
+    Keys,
+}
+
+/// A field that tabular and JSON output can include for each name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Column {
+    Name,
+    Surname,
+    Given,
+    /// Stroke count of the surname.
+    Strokes,
+    /// Pinyin of every character with tone numbers, `?` for characters
+    /// missing from the pinyin dictionary.
+    Pinyin,
+    /// The hexadecimal sort key, see [`NameSorter::sort_key`].
+    Key,
+    /// Stroke sequence of every character, as compared when sorting, `?` for
+    /// characters missing from the dictionary.
+    Order,
+    /// The name with variant characters folded, see
+    /// [`NameSorter::canonical_name`], for finding duplicates.
+    Canonical,
+}
+
+impl Column {
+    pub fn name(self) -> &'static str {
+        match self {
+            Column::Name => "name",
+            Column::Surname => "surname",
+            Column::Given => "given",
+            Column::Strokes => "strokes",
+            Column::Pinyin => "pinyin",
+            Column::Order => "order",
+            Column::Key => "key",
+            Column::Canonical => "canonical",
+        }
+    }
+
+    pub(crate) fn value(self, sorter: &NameSorter, name: &str) -> Value {
+        match self {
+            Column::Name => sorter.display_name(name).into(),
+            Column::Surname => sorter.split(name).0.into(),
+            Column::Given => sorter.split(name).1.into(),
+            Column::Strokes => sorter.stroke_count(&sorter.split(name).0).into(),
+            Column::Pinyin => sorter
+                .pinyin(name)
+                .iter()
+                .map(|reading| match reading {
+                    Some((syllable, tone)) => format!("{}{}", syllable, tone),
+                    None => "?".to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+                .into(),
+            Column::Key => sorter.sort_key_hex(name).into(),
+            Column::Canonical => sorter.canonical_name(name).into(),
+            Column::Order => sorter
+                .stroke_orders(&sorter.display_name(name))
+                .iter()
+                .map(|order| order.unwrap_or("?"))
+                .collect::<Vec<_>>()
+                .join(" ")
+                .into(),
+        }
+    }
+}
+
+impl fmt::Display for Column {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Column {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "name" => Ok(Column::Name),
+            "surname" => Ok(Column::Surname),
+            "given" => Ok(Column::Given),
+            "strokes" => Ok(Column::Strokes),
+            "pinyin" => Ok(Column::Pinyin),
+            "order" => Ok(Column::Order),
+            "key" => Ok(Column::Key),
+            "canonical" => Ok(Column::Canonical),
+            _ => Err(format!(
+                "unknown column `{}`, expected one of name, surname, given, strokes, pinyin, order, key, canonical",
+                s
+            )),
+        }
+    }
+}
+
+pub fn write_names<W: Write>(
+    mut writer: W,
+    sorter: &NameSorter,
+    names: &[String],
+    format: &OutputFormat,
+) -> io::Result<()> {
+    match format {
+        OutputFormat::Lines => {
+            for name in names {
+                writeln!(writer, "{}", sorter.display_name(name))?;
+            }
+        }
+        OutputFormat::Separated(separator) => {
+            let names: Vec<_> = names.iter().map(|name| sorter.display_name(name)).collect();
+            writeln!(writer, "{}", names.join(separator))?;
+        }
+        OutputFormat::Csv(columns) => write_columns(&mut writer, sorter, names, columns, ",", csv_field)?,
+        OutputFormat::Tsv(columns) => write_columns(&mut writer, sorter, names, columns, "\t", tsv_field)?,
+        OutputFormat::Json(columns) => {
+            let rows: Vec<Value> = match columns.as_slice() {
+                [Column::Name] => names.iter().map(|name| Value::from(sorter.display_name(name))).collect(),
+                _ => names
+                    .iter()
+                    .map(|name| {
+                        let object: Map<String, Value> = columns
+                            .iter()
+                            .map(|column| (column.name().to_string(), column.value(sorter, name)))
+                            .collect();
+                        Value::Object(object)
+                    })
+                    .collect(),
+            };
+            serde_json::to_writer_pretty(&mut writer, &rows)?;
+            writeln!(writer)?;
+        }
+        OutputFormat::Grouped(style) => group::write_grouped(&mut writer, sorter, names, *style)?,
+        OutputFormat::Keys => {
+            for name in names {
+                writeln!(writer, "{}\t{}", sorter.display_name(name), sorter.sort_key_hex(name))?;
+            }
+        }
+    }
+    writer.flush()
+}
+
+fn write_columns<W: Write>(
+    writer: &mut W,
+    sorter: &NameSorter,
+    names: &[String],
+    columns: &[Column],
+    delimiter: &str,
+    field: fn(&str) -> String,
+) -> io::Result<()> {
+    let header: Vec<&str> = columns.iter().map(|column| column.name()).collect();
+    writeln!(writer, "{}", header.join(delimiter))?;
+    for name in names {
+        let row: Vec<String> = columns
+            .iter()
+            .map(|column| match column.value(sorter, name) {
+                Value::String(s) => field(&s),
+                value => value.to_string(),
+            })
+            .collect();
+        writeln!(writer, "{}", row.join(delimiter))?;
+    }
+    Ok(())
+}
+
+pub(crate) fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+pub(crate) fn tsv_field(s: &str) -> String {
+    s.replace(['\t', '\n', '\r'], " ")
+}
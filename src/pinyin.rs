@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+const SURNAME_READINGS: &str = include_str!("../surname_pinyin.txt");
+
+mod embedded {
+    include!(concat!(env!("OUT_DIR"), "/pinyin_dict.rs"));
+}
+
+/// Maps a character to its pinyin syllable and tone, 1-4 or 5 for the
+/// neutral tone. Syllables are lowercase ASCII with `v` standing for `ü`.
+#[derive(Clone)]
+pub struct PinyinDict {
+    entries: Entries,
+}
+
+#[derive(Clone)]
+enum Entries {
+    Static(&'static [(char, &'static str, u8)]),
+    Owned(Vec<(char, String, u8)>),
+}
+
+impl PinyinDict {
+    /// The dictionary built from `pinyin.txt` at compile time.
+    pub fn embedded() -> Self {
+        PinyinDict {
+            entries: Entries::Static(embedded::PINYIN_DICT),
+        }
+    }
+
+    pub fn get(&self, c: char) -> Option<(&str, u8)> {
+        match &self.entries {
+            Entries::Static(entries) => entries
+                .binary_search_by_key(&c, |&(c, _, _)| c)
+                .ok()
+                .map(|i| (entries[i].1, entries[i].2)),
+            Entries::Owned(entries) => entries
+                .binary_search_by_key(&c, |&(c, _, _)| c)
+                .ok()
+                .map(|i| (entries[i].1.as_str(), entries[i].2)),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match &self.entries {
+            Entries::Static(entries) => entries.len(),
+            Entries::Owned(entries) => entries.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for PinyinDict {
+    fn default() -> Self {
+        PinyinDict::embedded()
+    }
+}
+
+/// Later entries replace earlier ones for the same character.
+impl FromIterator<(char, String, u8)> for PinyinDict {
+    fn from_iter<I: IntoIterator<Item = (char, String, u8)>>(iter: I) -> Self {
+        let mut entries: Vec<(char, String, u8)> = iter.into_iter().collect();
+        entries.reverse();
+        entries.sort_by_key(|&(c, _, _)| c);
+        entries.dedup_by_key(|&mut (c, _, _)| c);
+        PinyinDict {
+            entries: Entries::Owned(entries),
+        }
+    }
+}
+
+/// Readings that characters take when used as a surname, such as 单 shàn
+/// or 尉迟 yùchí, which [`Collation::Pinyin`](crate::Collation::Pinyin) uses
+/// for the surname instead of the everyday reading.
+#[derive(Clone, Default)]
+pub struct SurnameReadings {
+    readings: HashMap<String, Vec<(String, u8)>>,
+}
+
+impl SurnameReadings {
+    /// The table built from `surname_pinyin.txt` at compile time.
+    pub fn embedded() -> Self {
+        read_surname_readings(SURNAME_READINGS.as_bytes()).expect("embedded surname readings are valid")
+    }
+
+    /// The reading of each character of `surname`, if the whole surname is listed.
+    pub fn get(&self, surname: &str) -> Option<&[(String, u8)]> {
+        self.readings.get(surname).map(Vec::as_slice)
+    }
+
+    pub fn insert(&mut self, surname: String, readings: Vec<(String, u8)>) {
+        self.readings.insert(surname, readings);
+    }
+
+    pub fn len(&self) -> usize {
+        self.readings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.readings.is_empty()
+    }
+}
+
+pub fn load_surname_readings<P: AsRef<Path>>(path: P) -> io::Result<SurnameReadings> {
+    read_surname_readings(BufReader::new(File::open(path)?))
+}
+
+/// Reads lines such as `尉迟 yu4 chi2`, one reading per character of the
+/// surname. Text after `#` is a comment.
+pub fn read_surname_readings<R: BufRead>(reader: R) -> io::Result<SurnameReadings> {
+    let mut table = SurnameReadings::default();
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        let entry = line.split('#').next().unwrap_or_default().trim();
+        if entry.is_empty() {
+            continue;
+        }
+        let mut fields = entry.split_whitespace();
+        let surname = fields.next().unwrap_or_default();
+        let readings: Option<Vec<(String, u8)>> = fields.map(parse_reading).collect();
+        match readings {
+            Some(readings) if readings.len() == surname.chars().count() => {
+                table.insert(surname.to_string(), readings);
+            }
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "line {}: `{}` is not a surname followed by one pinyin reading per character",
+                        number + 1,
+                        entry
+                    ),
+                ))
+            }
+        }
+    }
+    Ok(table)
+}
+
+/// Loads a dictionary in the `pinyin.txt` format, replacing the embedded one.
+pub fn load_pinyin_dict<P: AsRef<Path>>(path: P) -> io::Result<PinyinDict> {
+    read_pinyin_dict(BufReader::new(File::open(path)?))
+}
+
+/// Reads lines such as `张 zhang1`. Text after `#` is a comment.
+pub fn read_pinyin_dict<R: BufRead>(reader: R) -> io::Result<PinyinDict> {
+    let mut entries = Vec::new();
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        let entry = line.split('#').next().unwrap_or_default().trim();
+        if entry.is_empty() {
+            continue;
+        }
+        match parse_entry(entry) {
+            Some(entry) => entries.push(entry),
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("line {}: `{}` is not a character followed by a pinyin reading", number + 1, entry),
+                ))
+            }
+        }
+    }
+    Ok(entries.into_iter().collect())
+}
+
+fn parse_entry(entry: &str) -> Option<(char, String, u8)> {
+    let (word, reading) = entry.split_once(char::is_whitespace)?;
+    let mut chars = word.chars();
+    let (c, None) = (chars.next()?, chars.next()) else {
+        return None;
+    };
+    let (syllable, tone) = parse_reading(reading.trim())?;
+    Some((c, syllable, tone))
+}
+
+/// Splits `zhang1` into `("zhang", 1)`.
+pub(crate) fn parse_reading(reading: &str) -> Option<(String, u8)> {
+    let (at, _) = reading.char_indices().next_back()?;
+    let (syllable, tone) = reading.split_at(at);
+    let tone = tone.parse().ok().filter(|tone| (1..=5).contains(tone))?;
+    if syllable.is_empty() || !syllable.bytes().all(|b| b.is_ascii_lowercase()) {
+        return None;
+    }
+    Some((syllable.to_string(), tone))
+}
@@ -0,0 +1,171 @@
+use crate::{NameSorter, SortError};
+use serde_json::Value;
+use std::borrow::Cow;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+/// A layout for JSON records.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RecordFormat {
+    /// A single JSON array of records.
+    Json,
+    /// JSON Lines: one record per line.
+    JsonLines,
+}
+
+impl FromStr for RecordFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(RecordFormat::Json),
+            "jsonl" => Ok(RecordFormat::JsonLines),
+            _ => Err(format!("unknown record format `{}`, expected json or jsonl", s)),
+        }
+    }
+}
+
+/// A dot-separated path to a field of a JSON record, such as `name` or
+/// `person.name`; numeric segments index into arrays, as in `names.0`.
+/// `.` is the record itself, for arrays of plain strings.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldPath(Vec<String>);
+
+impl FieldPath {
+    pub fn get<'a>(&self, record: &'a Value) -> Option<&'a Value> {
+        self.0.iter().try_fold(record, |value, segment| match value {
+            Value::Object(object) => object.get(segment),
+            Value::Array(array) => array.get(segment.parse::<usize>().ok()?),
+            _ => None,
+        })
+    }
+
+    fn get_mut<'a>(&self, record: &'a mut Value) -> Option<&'a mut Value> {
+        self.0.iter().try_fold(record, |value, segment| match value {
+            Value::Object(object) => object.get_mut(segment),
+            Value::Array(array) => array.get_mut(segment.parse::<usize>().ok()?),
+            _ => None,
+        })
+    }
+
+    /// The name at this path, or `""` if the record has no string there.
+    pub fn name<'a>(&self, record: &'a Value) -> &'a str {
+        self.get(record).and_then(Value::as_str).unwrap_or("")
+    }
+}
+
+impl FromStr for FieldPath {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "." {
+            return Ok(FieldPath(Vec::new()));
+        }
+        if s.is_empty() || s.split('.').any(str::is_empty) {
+            return Err(format!("field path `{}` has an empty segment, use `.` for the whole record", s));
+        }
+        Ok(FieldPath(s.split('.').map(str::to_string).collect()))
+    }
+}
+
+impl fmt::Display for FieldPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            f.write_str(".")
+        } else {
+            f.write_str(&self.0.join("."))
+        }
+    }
+}
+
+/// Sorts `records` by the name at `path` with
+/// [`NameSorter::try_sort_by_name`]. Records without a string there sort as
+/// an empty name.
+pub fn sort_records(sorter: &NameSorter, records: &mut [Value], path: &FieldPath) -> Result<(), SortError> {
+    sorter.try_sort_by_name(records, |record| path.name(record))
+}
+
+pub fn load_records<P: AsRef<Path>>(path: P, format: RecordFormat) -> io::Result<Vec<Value>> {
+    read_records(BufReader::new(File::open(path)?), format)
+}
+
+/// Reads a JSON array of records, or one record per line skipping blank
+/// lines. Errors name the line they were found on.
+pub fn read_records<R: BufRead>(mut reader: R, format: RecordFormat) -> io::Result<Vec<Value>> {
+    match format {
+        RecordFormat::Json => {
+            let mut json = String::new();
+            reader.read_to_string(&mut json)?;
+            match serde_json::from_str(&json)? {
+                Value::Array(records) => Ok(records),
+                _ => Err(io::Error::new(io::ErrorKind::InvalidData, "expected a JSON array of records")),
+            }
+        }
+        RecordFormat::JsonLines => {
+            let mut records = Vec::new();
+            for (number, line) in reader.lines().enumerate() {
+                let line = line?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let record = serde_json::from_str(&line).map_err(|err| {
+                    io::Error::new(io::ErrorKind::InvalidData, format!("line {}: {}", number + 1, err))
+                })?;
+                records.push(record);
+            }
+            Ok(records)
+        }
+    }
+}
+
+/// Writes `records` as a pretty-printed array or one compact record per line,
+/// with the name at `path` shown without the surname delimiter, as
+/// [`NameSorter::display_name`] does, and everything else as it was read.
+pub fn write_records<W: Write>(
+    mut writer: W,
+    sorter: &NameSorter,
+    records: &[Value],
+    path: &FieldPath,
+    format: RecordFormat,
+) -> io::Result<()> {
+    let records = records.iter().map(|record| display_record(sorter, record, path));
+    match format {
+        RecordFormat::Json => {
+            serde_json::to_writer_pretty(&mut writer, &records.collect::<Vec<_>>())?;
+            writeln!(writer)?;
+        }
+        RecordFormat::JsonLines => {
+            for record in records {
+                serde_json::to_writer(&mut writer, &record)?;
+                writeln!(writer)?;
+            }
+        }
+    }
+    writer.flush()
+}
+
+pub fn write_records_output<P: AsRef<Path>>(
+    path: P,
+    sorter: &NameSorter,
+    records: &[Value],
+    name_field: &FieldPath,
+    format: RecordFormat,
+) -> io::Result<()> {
+    write_records(BufWriter::new(File::create(path)?), sorter, records, name_field, format)
+}
+
+fn display_record<'a>(sorter: &NameSorter, record: &'a Value, path: &FieldPath) -> Cow<'a, Value> {
+    match sorter.display_name(path.name(record)) {
+        Cow::Borrowed(_) => Cow::Borrowed(record),
+        Cow::Owned(name) => {
+            let mut record = record.clone();
+            if let Some(value) = path.get_mut(&mut record) {
+                *value = Value::String(name);
+            }
+            Cow::Owned(record)
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+const COMPOUND_SURNAMES: &str = include_str!("../compound_surnames.txt");
+
+/// The compound surname list compiled into the crate.
+pub fn embedded_compound_surnames_set() -> HashSet<String> {
+    read_compound_surnames_set(COMPOUND_SURNAMES.as_bytes()).expect("embedded compound surname list is valid")
+}
+
+pub fn load_compound_surnames_set<P: AsRef<Path>>(path: P) -> io::Result<HashSet<String>> {
+    read_compound_surnames_set(BufReader::new(File::open(path)?))
+}
+
+/// Reads one compound surname per line. Text after `#` is a comment, blank
+/// lines are skipped, whitespace inside an entry is removed and duplicates
+/// are merged. Any other entry that is not two or more CJK characters fails
+/// the whole list with an `InvalidData` error naming every offending line.
+pub fn read_compound_surnames_set<R: BufRead>(reader: R) -> io::Result<HashSet<String>> {
+    let mut surnames = HashSet::new();
+    let mut problems = Vec::new();
+
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        let entry: String = line
+            .split('#')
+            .next()
+            .unwrap_or_default()
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect();
+        if entry.is_empty() {
+            continue;
+        }
+        if entry.chars().count() >= 2 && entry.chars().all(is_cjk) {
+            surnames.insert(entry);
+        } else {
+            problems.push(format!("line {}: `{}` is not two or more CJK characters", number + 1, line.trim()));
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(surnames)
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid compound surname list:\n  {}", problems.join("\n  ")),
+        ))
+    }
+}
+
+/// Multi-character surnames, matched against the start of a name.
+#[derive(Clone, Debug, Default)]
+pub struct SurnameTrie {
+    children: HashMap<char, SurnameTrie>,
+    terminal: bool,
+}
+
+impl SurnameTrie {
+    pub fn new() -> Self {
+        SurnameTrie::default()
+    }
+
+    pub fn insert(&mut self, surname: &str) {
+        let node = surname
+            .chars()
+            .fold(self, |node, c| node.children.entry(c).or_default());
+        node.terminal = true;
+    }
+
+    pub fn contains(&self, surname: &str) -> bool {
+        let mut node = self;
+        for c in surname.chars() {
+            match node.children.get(&c) {
+                Some(child) => node = child,
+                None => return false,
+            }
+        }
+        node.terminal
+    }
+
+    /// Byte length of the longest surname that `name` starts with.
+    pub fn longest_prefix(&self, name: &str) -> Option<usize> {
+        let mut node = self;
+        let mut longest = None;
+        for (i, c) in name.char_indices() {
+            match node.children.get(&c) {
+                Some(child) => node = child,
+                None => break,
+            }
+            if node.terminal {
+                longest = Some(i + c.len_utf8());
+            }
+        }
+        longest
+    }
+}
+
+impl<S: AsRef<str>> FromIterator<S> for SurnameTrie {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        let mut trie = SurnameTrie::new();
+        for surname in iter {
+            trie.insert(surname.as_ref());
+        }
+        trie
+    }
+}
+
+impl From<HashSet<String>> for SurnameTrie {
+    fn from(set: HashSet<String>) -> Self {
+        set.iter().collect()
+    }
+}
+
+/// CJK Unified Ideographs, their extensions and the compatibility ideographs.
+pub(crate) fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3400}'..='\u{4DBF}'
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{F900}'..='\u{FAFF}'
+        | '\u{20000}'..='\u{2FA1F}'
+        | '\u{30000}'..='\u{323AF}')
+}
@@ -0,0 +1,409 @@
+use crate::output::{csv_field, tsv_field};
+use crate::{Column, NameSorter, SortError, TieBreak, UnknownChars, UnknownPolicy};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+/// A delimited text layout for tables of names.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TableFormat {
+    /// Comma-separated values, fields quoted with `"` where needed.
+    Csv,
+    /// Tab-separated values, without quoting.
+    Tsv,
+}
+
+impl TableFormat {
+    fn delimiter(self) -> u8 {
+        match self {
+            TableFormat::Csv => b',',
+            TableFormat::Tsv => b'\t',
+        }
+    }
+}
+
+impl FromStr for TableFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(TableFormat::Csv),
+            "tsv" => Ok(TableFormat::Tsv),
+            _ => Err(format!("unknown table format `{}`, expected csv or tsv", s)),
+        }
+    }
+}
+
+/// A column of a table, by position or by the name in its header row.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ColumnRef {
+    /// Zero-based position; parsed from and displayed as a one-based number.
+    Index(usize),
+    Header(String),
+}
+
+impl FromStr for ColumnRef {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.parse::<usize>() {
+            Ok(0) => Err("column numbers start at 1".to_string()),
+            Ok(n) => Ok(ColumnRef::Index(n - 1)),
+            Err(_) if s.is_empty() => Err("column must be a number or a header name".to_string()),
+            Err(_) => Ok(ColumnRef::Header(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for ColumnRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColumnRef::Index(i) => write!(f, "column {}", i + 1),
+            ColumnRef::Header(name) => write!(f, "column `{}`", name),
+        }
+    }
+}
+
+/// How the cells of a column are compared in a multi-key sort.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KeyKind {
+    /// Plain text, by code point.
+    #[default]
+    Text,
+    /// Decimal numbers; cells that are not numbers, such as blanks, sort
+    /// after all numbers in either direction, as text.
+    Number,
+    /// Names, by the sorter's collation.
+    Name,
+}
+
+/// One key of a multi-key sort: a column, how to compare it, and in which
+/// direction. Parsed from `COLUMN[:text|number|name][:asc|desc]`, such as
+/// `部门`, `年级:number:desc` or `姓名:name`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TableKey {
+    pub column: ColumnRef,
+    pub kind: KeyKind,
+    pub descending: bool,
+}
+
+impl FromStr for TableKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(':');
+        let column = parts.next().unwrap_or_default().trim().parse()?;
+        let mut key = TableKey { column, kind: KeyKind::default(), descending: false };
+        for part in parts {
+            match part.trim() {
+                "text" => key.kind = KeyKind::Text,
+                "number" => key.kind = KeyKind::Number,
+                "name" => key.kind = KeyKind::Name,
+                "asc" => key.descending = false,
+                "desc" => key.descending = true,
+                other => {
+                    return Err(format!(
+                        "unknown sort key option `{}` in `{}`, expected text, number, name, asc or desc",
+                        other, s
+                    ))
+                }
+            }
+        }
+        Ok(key)
+    }
+}
+
+/// A cell prepared for comparison under its key's [`KeyKind`].
+enum Field {
+    Text(String),
+    Number(f64),
+    NotANumber(String),
+    Name(Vec<u8>),
+}
+
+impl Field {
+    fn new(sorter: &NameSorter, kind: KeyKind, cell: &str) -> Self {
+        match kind {
+            KeyKind::Text => Field::Text(cell.to_string()),
+            KeyKind::Number => match cell.parse::<f64>() {
+                Ok(n) if !n.is_nan() => Field::Number(n),
+                _ => Field::NotANumber(cell.to_string()),
+            },
+            KeyKind::Name => Field::Name(sorter.sort_key(cell)),
+        }
+    }
+
+    /// Cells that are not numbers stay after the numbers when descending.
+    fn compare(&self, other: &Field, descending: bool) -> Ordering {
+        let ordering = match (self, other) {
+            (Field::Number(a), Field::Number(b)) => a.total_cmp(b),
+            (Field::Number(_), Field::NotANumber(_)) => return Ordering::Less,
+            (Field::NotANumber(_), Field::Number(_)) => return Ordering::Greater,
+            (Field::Text(a), Field::Text(b)) | (Field::NotANumber(a), Field::NotANumber(b)) => a.cmp(b),
+            (Field::Name(a), Field::Name(b)) => a.cmp(b),
+            _ => Ordering::Equal,
+        };
+        if descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+}
+
+/// What a workbook cell held, so it can be written back as the same type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CellKind {
+    Text,
+    Number,
+    Bool,
+    /// A date without a time of day, as text such as `2024-09-01`.
+    Date,
+    /// A date and time, as text such as `2024-09-01 08:30:00`.
+    DateTime,
+}
+
+/// Rows read from a CSV or TSV file or a workbook, sorted by one of their
+/// columns and otherwise carried through unchanged.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Table {
+    pub header: Option<Vec<String>>,
+    pub rows: Vec<Vec<String>>,
+    /// The kind of each cell of `rows`, row by row, for tables read from a
+    /// workbook. Empty for CSV and TSV, whose cells are all text.
+    pub kinds: Vec<Vec<CellKind>>,
+}
+
+impl Table {
+    /// The position of `column`, failing with [`io::ErrorKind::InvalidInput`]
+    /// if the table has no such column.
+    pub fn column_index(&self, column: &ColumnRef) -> io::Result<usize> {
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidInput, message);
+        match column {
+            ColumnRef::Index(i) => {
+                let width = self.header.iter().chain(&self.rows).map(Vec::len).max().unwrap_or(0);
+                if *i < width || width == 0 {
+                    Ok(*i)
+                } else {
+                    Err(invalid(format!("{} is past the last column, {}", column, width)))
+                }
+            }
+            ColumnRef::Header(name) => {
+                let header = self
+                    .header
+                    .as_ref()
+                    .ok_or_else(|| invalid(format!("cannot find {} in a table without a header row", column)))?;
+                header
+                    .iter()
+                    .position(|cell| cell.trim() == name)
+                    .ok_or_else(|| invalid(format!("no {} in the header row", column)))
+            }
+        }
+    }
+
+    /// Sorts the rows by the names in column `index` with
+    /// [`NameSorter::try_sort_by_name`]. Rows too short to have the column
+    /// sort as an empty name.
+    pub fn sort(&mut self, sorter: &NameSorter, index: usize) -> Result<(), SortError> {
+        self.with_kinds(|rows| sorter.try_sort_by_name(rows, |(row, _)| cell(row, index)))
+    }
+
+    /// Sorts the rows by several keys in turn, such as a department column
+    /// and then a name column. Rows equal on every key are ordered as the
+    /// sorter's [`TieBreak`] says, and under [`UnknownPolicy::Error`] unknown
+    /// characters in [`KeyKind::Name`] columns are rejected. Missing columns
+    /// fail with [`io::ErrorKind::InvalidInput`] and sorting errors with
+    /// [`io::ErrorKind::InvalidData`] wrapping a [`SortError`].
+    pub fn sort_by_keys(&mut self, sorter: &NameSorter, keys: &[TableKey]) -> io::Result<()> {
+        let invalid_data = |err: SortError| io::Error::new(io::ErrorKind::InvalidData, err);
+        let columns = keys
+            .iter()
+            .map(|key| Ok((self.column_index(&key.column)?, key)))
+            .collect::<io::Result<Vec<_>>>()?;
+        let name_columns: Vec<usize> =
+            columns.iter().filter(|(_, key)| key.kind == KeyKind::Name).map(|&(index, _)| index).collect();
+
+        if sorter.unknown_policy == UnknownPolicy::Error {
+            let names: Vec<&str> = name_columns.iter().flat_map(|&index| self.names(index)).collect();
+            let unknown = sorter.unknown_chars(&names);
+            if !unknown.is_empty() {
+                return Err(invalid_data(UnknownChars(unknown, sorter.collation).into()));
+            }
+        }
+
+        self.with_kinds(|rows| {
+            if sorter.tie_break == TieBreak::ReverseInputOrder {
+                rows.reverse();
+            }
+            sort_rows(sorter, rows, &columns, &name_columns)
+        })
+    }
+
+
+    /// Appends `columns`, such as the surname or its stroke count, computed
+    /// from the names in column `index`. Short rows are padded with empty
+    /// cells first so the new columns line up.
+    pub fn add_columns(&mut self, sorter: &NameSorter, index: usize, columns: &[Column]) {
+        if columns.is_empty() {
+            return;
+        }
+        let width = self.header.iter().chain(&self.rows).map(Vec::len).max().unwrap_or(0);
+        if let Some(header) = &mut self.header {
+            header.resize(width, String::new());
+            header.extend(columns.iter().map(|column| column.name().to_string()));
+        }
+        for kinds in &mut self.kinds {
+            kinds.resize(width, CellKind::Text);
+        }
+        for row in &mut self.rows {
+            let name = cell(row, index).to_string();
+            row.resize(width, String::new());
+            row.extend(columns.iter().map(|column| match column.value(sorter, &name) {
+                Value::String(s) => s,
+                Value::Null => String::new(),
+                value => value.to_string(),
+            }));
+        }
+    }
+
+    /// Runs `f` on the rows paired with their cell kinds, then puts them back.
+    fn with_kinds<T>(&mut self, f: impl FnOnce(&mut Vec<Row>) -> T) -> T {
+        let known = !self.kinds.is_empty();
+        let kinds = std::mem::take(&mut self.kinds).into_iter().chain(std::iter::repeat_with(Vec::new));
+        let mut rows: Vec<Row> = std::mem::take(&mut self.rows).into_iter().zip(kinds).collect();
+        let result = f(&mut rows);
+        (self.rows, self.kinds) = rows.into_iter().unzip();
+        if !known {
+            self.kinds.clear();
+        }
+        result
+    }
+
+    /// The names in column `index`, for [`NameSorter::unknown_chars`].
+    pub fn names(&self, index: usize) -> Vec<&str> {
+        self.rows.iter().map(|row| cell(row, index)).collect()
+    }
+}
+
+fn cell(row: &[String], index: usize) -> &str {
+    row.get(index).map_or("", |cell| cell.trim())
+}
+
+/// A row and the kinds of its cells, if known.
+type Row = (Vec<String>, Vec<CellKind>);
+
+/// Sorts `rows` by the cells in `columns`, as [`Table::sort_by_keys`] does,
+/// leaving them sorted even if two different names tie.
+fn sort_rows(
+    sorter: &NameSorter,
+    rows: &mut Vec<Row>,
+    columns: &[(usize, &TableKey)],
+    name_columns: &[usize],
+) -> io::Result<()> {
+    let mut keyed: Vec<(Vec<Field>, Row)> = std::mem::take(rows)
+        .into_iter()
+        .map(|row| {
+            let fields = columns.iter().map(|&(index, key)| Field::new(sorter, key.kind, cell(&row.0, index))).collect();
+            (fields, row)
+        })
+        .collect();
+    let compare = |a: &[Field], b: &[Field]| {
+        a.iter()
+            .zip(b)
+            .zip(columns)
+            .map(|((a, b), (_, key))| a.compare(b, key.descending))
+            .find(|&ordering| ordering != Ordering::Equal)
+            .unwrap_or(Ordering::Equal)
+    };
+    keyed.sort_by(|(a, _), (b, _)| compare(a, b));
+
+    let mut tie = None;
+    if sorter.tie_break == TieBreak::Error {
+        tie = keyed.windows(2).find_map(|pair| {
+            let ((fields_a, (a, _)), (fields_b, (b, _))) = (&pair[0], &pair[1]);
+            if compare(fields_a, fields_b) != Ordering::Equal {
+                return None;
+            }
+            let index = name_columns.iter().find(|&&index| cell(a, index) != cell(b, index))?;
+            Some(SortError::Tie(cell(a, *index).to_string(), cell(b, *index).to_string()))
+        });
+    }
+    *rows = keyed.into_iter().map(|(_, row)| row).collect();
+    match tie {
+        Some(err) => Err(io::Error::new(io::ErrorKind::InvalidData, err)),
+        None => Ok(()),
+    }
+}
+
+pub fn load_table<P: AsRef<Path>>(path: P, format: TableFormat, has_header: bool) -> io::Result<Table> {
+    read_table(File::open(path)?, format, has_header)
+}
+
+/// Reads a table, its first row being the header if `has_header` is set.
+/// Rows may have different numbers of fields, and a leading byte order mark
+/// is dropped.
+pub fn read_table<R: Read>(reader: R, format: TableFormat, has_header: bool) -> io::Result<Table> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(format.delimiter())
+        .quoting(format == TableFormat::Csv)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(reader);
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record: Vec<String> = record?.iter().map(str::to_string).collect();
+        rows.push(record);
+    }
+    if let Some(first) = rows.first_mut().and_then(|row| row.first_mut()) {
+        if let Some(stripped) = first.strip_prefix('\u{feff}') {
+            *first = stripped.to_string();
+        }
+    }
+
+    let header = if has_header && !rows.is_empty() { Some(rows.remove(0)) } else { None };
+    Ok(Table { header, rows, kinds: Vec::new() })
+}
+
+/// Writes `table` with the names in column `index` shown without the
+/// surname delimiter, as [`NameSorter::display_name`] does, and every other
+/// cell as it was read.
+pub fn write_table<W: Write>(
+    mut writer: W,
+    sorter: &NameSorter,
+    table: &Table,
+    index: usize,
+    format: TableFormat,
+) -> io::Result<()> {
+    let (delimiter, field): (&str, fn(&str) -> String) = match format {
+        TableFormat::Csv => (",", csv_field),
+        TableFormat::Tsv => ("\t", tsv_field),
+    };
+    if let Some(header) = &table.header {
+        let header: Vec<String> = header.iter().map(|cell| field(cell)).collect();
+        writeln!(writer, "{}", header.join(delimiter))?;
+    }
+    for row in &table.rows {
+        let row: Vec<String> = row
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| if i == index { field(&sorter.display_name(cell)) } else { field(cell) })
+            .collect();
+        writeln!(writer, "{}", row.join(delimiter))?;
+    }
+    writer.flush()
+}
+
+pub fn write_table_output<P: AsRef<Path>>(
+    path: P,
+    sorter: &NameSorter,
+    table: &Table,
+    index: usize,
+    format: TableFormat,
+) -> io::Result<()> {
+    write_table(BufWriter::new(File::create(path)?), sorter, table, index, format)
+}
@@ -0,0 +1,64 @@
+use crate::Collation;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// What to do with characters that are missing from the dictionary the
+/// collation looks them up in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UnknownPolicy {
+    /// Refuse to sort, see [`NameSorter::try_sort`](crate::NameSorter::try_sort).
+    Error,
+    /// Sort unknown characters after every known one.
+    #[default]
+    Last,
+    /// Sort unknown characters before every known one.
+    First,
+    /// Treat unknown characters as having this many strokes, placed after
+    /// the known characters with the same count.
+    Strokes(usize),
+}
+
+impl FromStr for UnknownPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "error" => Ok(UnknownPolicy::Error),
+            "last" => Ok(UnknownPolicy::Last),
+            "first" => Ok(UnknownPolicy::First),
+            _ => match s.parse() {
+                Ok(strokes) if strokes > 0 => Ok(UnknownPolicy::Strokes(strokes)),
+                _ => Err(format!(
+                    "invalid unknown character policy `{}`, expected error, last, first or a stroke count",
+                    s
+                )),
+            },
+        }
+    }
+}
+
+/// A character missing from the dictionary, with the names it appears in.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownChar {
+    pub ch: char,
+    pub names: Vec<String>,
+}
+
+/// The characters that made [`NameSorter::try_sort`](crate::NameSorter::try_sort)
+/// fail under [`UnknownPolicy::Error`], and the collation they were looked
+/// up for, which names the dictionary they are missing from.
+#[derive(Debug)]
+pub struct UnknownChars(pub Vec<UnknownChar>, pub Collation);
+
+impl fmt::Display for UnknownChars {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} character(s) not in the {}:", self.0.len(), self.1.dictionary())?;
+        for unknown in &self.0 {
+            write!(f, "\n  {} (U+{:04X}): {}", unknown.ch, unknown.ch as u32, unknown.names.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for UnknownChars {}
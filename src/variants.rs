@@ -0,0 +1,105 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+const VARIANTS: &str = include_str!("../variants.txt");
+
+/// Maps characters to the form they are looked up under when variants are
+/// folded, such as traditional 陳 to simplified 陈 or the variant 峯 to 峰.
+#[derive(Clone, Default)]
+pub struct VariantMap {
+    map: HashMap<char, char>,
+}
+
+impl VariantMap {
+    /// The table built from `variants.txt` at compile time.
+    pub fn embedded() -> Self {
+        read_variants(VARIANTS.as_bytes()).expect("embedded variants are valid")
+    }
+
+    pub fn get(&self, c: char) -> Option<char> {
+        self.map.get(&c).copied()
+    }
+
+    /// `text` with every listed character replaced by its mapped form.
+    pub fn fold<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        if text.chars().any(|c| self.map.contains_key(&c)) {
+            Cow::Owned(text.chars().map(|c| self.get(c).unwrap_or(c)).collect())
+        } else {
+            Cow::Borrowed(text)
+        }
+    }
+
+    pub fn insert(&mut self, from: char, to: char) {
+        self.map.insert(from, to);
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
+
+/// Later pairs replace earlier ones for the same character.
+impl FromIterator<(char, char)> for VariantMap {
+    fn from_iter<I: IntoIterator<Item = (char, char)>>(iter: I) -> Self {
+        VariantMap {
+            map: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// Names that differ only by variant characters, as found by
+/// [`NameSorter::variant_duplicates`](crate::NameSorter::variant_duplicates).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VariantDuplicates(pub Vec<Vec<String>>);
+
+impl fmt::Display for VariantDuplicates {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} name(s) written with different variants:", self.0.len())?;
+        for names in &self.0 {
+            write!(f, "\n  {}", names.join(" = "))?;
+        }
+        Ok(())
+    }
+}
+
+pub fn load_variants<P: AsRef<Path>>(path: P) -> io::Result<VariantMap> {
+    read_variants(BufReader::new(File::open(path)?))
+}
+
+/// Reads lines such as `陳 陈`, a character followed by the form it folds
+/// to. Text after `#` is a comment.
+pub fn read_variants<R: BufRead>(reader: R) -> io::Result<VariantMap> {
+    let mut variants = VariantMap::default();
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        let entry = line.split('#').next().unwrap_or_default().trim();
+        if entry.is_empty() {
+            continue;
+        }
+        let mut chars = entry.split_whitespace().map(|field| {
+            let mut chars = field.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Some(c),
+                _ => None,
+            }
+        });
+        match (chars.next(), chars.next(), chars.next()) {
+            (Some(Some(from)), Some(Some(to)), None) => variants.insert(from, to),
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("line {}: `{}` is not a character followed by its variant", number + 1, entry),
+                ))
+            }
+        }
+    }
+    Ok(variants)
+}
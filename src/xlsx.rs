@@ -0,0 +1,154 @@
+use crate::{CellKind, NameSorter, Table};
+use calamine::{open_workbook_from_rs, Data, ExcelDateTime, ExcelDateTimeType, Reader, Xlsx};
+use rust_xlsxwriter::{Format, Workbook};
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, Write};
+use std::path::Path;
+
+/// Reads a sheet of an .xlsx workbook, the first one unless `sheet` names
+/// another, as a [`Table`]. Column numbers match the sheet's, blank rows are
+/// skipped, and numbers, dates and other values become their text: `3`,
+/// `2024-09-01`, `TRUE`, with their type kept in [`Table::kinds`].
+pub fn load_xlsx<P: AsRef<Path>>(path: P, sheet: Option<&str>, has_header: bool) -> io::Result<Table> {
+    read_xlsx(BufReader::new(File::open(path)?), sheet, has_header)
+}
+
+pub fn read_xlsx<R: Read + Seek>(reader: R, sheet: Option<&str>, has_header: bool) -> io::Result<Table> {
+    let mut workbook: Xlsx<R> = open_workbook_from_rs(reader).map_err(invalid_data)?;
+    let sheets = workbook.sheet_names();
+    let name = match sheet {
+        Some(name) if sheets.iter().any(|sheet| sheet == name) => name.to_string(),
+        Some(name) => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("no sheet named `{}`, the workbook has {}", name, sheets.join(", ")),
+            ))
+        }
+        None => sheets
+            .first()
+            .cloned()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "the workbook has no sheets"))?,
+    };
+    let range = workbook.worksheet_range(&name).map_err(invalid_data)?;
+
+    let skipped_columns = range.start().map_or(0, |(_, column)| column as usize);
+    let (mut rows, mut kinds): (Vec<Vec<String>>, Vec<Vec<CellKind>>) = range
+        .rows()
+        .filter(|row| row.iter().any(|cell| *cell != Data::Empty))
+        .map(|row| {
+            let mut cells = vec![String::new(); skipped_columns];
+            let mut kinds = vec![CellKind::Text; skipped_columns];
+            cells.extend(row.iter().map(cell_text));
+            kinds.extend(row.iter().map(cell_kind));
+            (cells, kinds)
+        })
+        .unzip();
+
+    let header = if has_header && !rows.is_empty() {
+        kinds.remove(0);
+        Some(rows.remove(0))
+    } else {
+        None
+    };
+    Ok(Table { header, rows, kinds })
+}
+
+fn cell_text(cell: &Data) -> String {
+    match cell {
+        Data::Bool(true) => "TRUE".to_string(),
+        Data::Bool(false) => "FALSE".to_string(),
+        Data::DateTime(datetime) if datetime.is_datetime() => {
+            let (year, month, day, hour, minute, second) = to_nearest_second(datetime);
+            if (hour, minute, second) == (0, 0, 0) {
+                format!("{:04}-{:02}-{:02}", year, month, day)
+            } else {
+                format!("{:04}-{:02}-{:02} {:02}:{:02}:{:02}", year, month, day, hour, minute, second)
+            }
+        }
+        cell => cell.to_string(),
+    }
+}
+
+/// The date and time of `datetime` to the nearest second, as Excel shows it.
+/// Serial values often fall a fraction of a millisecond short of the time
+/// that was entered, such as 08:29:59.999 for 08:30.
+fn to_nearest_second(datetime: &ExcelDateTime) -> (u16, u8, u8, u8, u8, u8) {
+    let value = datetime.as_f64();
+    let is_1904 = *datetime != ExcelDateTime::new(value, ExcelDateTimeType::DateTime, false);
+    let rounded = ExcelDateTime::new(value + 0.5 / 86_400.0, ExcelDateTimeType::DateTime, is_1904);
+    let (year, month, day, hour, minute, second, _) = rounded.to_ymd_hms_milli();
+    (year, month, day, hour, minute, second)
+}
+
+fn cell_kind(cell: &Data) -> CellKind {
+    match cell {
+        Data::Int(_) | Data::Float(_) => CellKind::Number,
+        Data::Bool(_) => CellKind::Bool,
+        Data::DateTime(datetime) if datetime.is_datetime() => match to_nearest_second(datetime) {
+            (_, _, _, 0, 0, 0) => CellKind::Date,
+            _ => CellKind::DateTime,
+        },
+        _ => CellKind::Text,
+    }
+}
+
+/// Writes `table` as a new single-sheet .xlsx workbook, with a bold header
+/// row and the names in column `index` shown without the surname delimiter.
+/// Cells keep the type given by [`Table::kinds`]. Cells of unknown type, as
+/// read from CSV or added by [`Table::add_columns`], are written as numbers
+/// if they hold a number in its plain form, such as `42` or `3.5` but not
+/// `007`, and as text otherwise.
+pub fn write_xlsx<W: Write>(mut writer: W, sorter: &NameSorter, table: &Table, index: usize) -> io::Result<()> {
+    let mut workbook = Workbook::new();
+    let worksheet = workbook.add_worksheet();
+    let bold = Format::new().set_bold();
+    let date = Format::new().set_num_format("yyyy-mm-dd");
+    let datetime = Format::new().set_num_format("yyyy-mm-dd hh:mm:ss");
+
+    let mut row_number = 0;
+    if let Some(header) = &table.header {
+        for (column, cell) in header.iter().enumerate() {
+            worksheet.write_string_with_format(0, column as u16, cell, &bold).map_err(io::Error::other)?;
+        }
+        row_number = 1;
+    }
+    for (i, row) in table.rows.iter().enumerate() {
+        for (column, cell) in row.iter().enumerate() {
+            let cell = if column == index { sorter.display_name(cell) } else { cell.into() };
+            if cell.is_empty() {
+                continue;
+            }
+            let kind = table.kinds.get(i).and_then(|kinds| kinds.get(column)).copied();
+            let column = column as u16;
+            let number = cell.parse::<f64>().ok().filter(|number| number.is_finite());
+            let written = match (kind, number) {
+                (Some(CellKind::Number), Some(number)) => worksheet.write_number(row_number, column, number),
+                (None, Some(number)) if number.to_string() == cell => worksheet.write_number(row_number, column, number),
+                (Some(CellKind::Bool), _) if cell == "TRUE" || cell == "FALSE" => {
+                    worksheet.write_boolean(row_number, column, cell == "TRUE")
+                }
+                (Some(kind @ (CellKind::Date | CellKind::DateTime)), _) => match rust_xlsxwriter::ExcelDateTime::parse_from_str(&cell) {
+                    Ok(value) => {
+                        let format = if kind == CellKind::Date { &date } else { &datetime };
+                        worksheet.write_datetime_with_format(row_number, column, value, format)
+                    }
+                    Err(_) => worksheet.write_string(row_number, column, cell),
+                },
+                _ => worksheet.write_string(row_number, column, cell),
+            };
+            written.map_err(io::Error::other)?;
+        }
+        row_number += 1;
+    }
+
+    writer.write_all(&workbook.save_to_buffer().map_err(io::Error::other)?)?;
+    writer.flush()
+}
+
+pub fn write_xlsx_output<P: AsRef<Path>>(path: P, sorter: &NameSorter, table: &Table, index: usize) -> io::Result<()> {
+    write_xlsx(File::create(path)?, sorter, table, index)
+}
+
+fn invalid_data<E: std::error::Error + Send + Sync + 'static>(err: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
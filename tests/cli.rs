@@ -0,0 +1,55 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn run(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_SortChineseName"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn sorts_stdin_to_stdout() {
+    let output = run(&["-", "--output=-"], "李四\n王五\n");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "王五\n李四\n");
+}
+
+#[test]
+fn invalid_arguments_exit_with_status_2() {
+    let output = run(&["-", "-o", "-", "-f", "csv", "--separator", ","], "王五\n");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.starts_with("error: `--separator` cannot be combined with `--format`"), "{}", stderr);
+}
+
+#[test]
+fn sorting_errors_exit_with_status_1() {
+    let output = run(&["-", "-o", "-", "--unknown", "error"], "王五\nA\n");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn failed_run_sort_leaves_the_output_alone() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("out.txt");
+    std::fs::write(&path, "old\n").unwrap();
+    let out = path.to_str().unwrap();
+
+    // 丁一 is merged first; the tie between 甲一 and 申一 comes after it.
+    let output = run(&["-", "-o", out, "--run-size", "1", "--ties", "error"], "丁一\n申一\n甲一\n");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "old\n");
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+    let output = run(&["-", "-o", out, "--run-size", "1"], "申一\n丁一\n");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "丁一\n申一\n");
+}
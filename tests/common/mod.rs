@@ -0,0 +1,34 @@
+//! Fixtures shared by the integration tests and the benchmark.
+#![allow(dead_code)]
+
+use std::ops::Range;
+
+/// A xorshift generator, so generated names are the same on every run.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    /// A number in `0..bound`.
+    pub fn below(&mut self, bound: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % bound as u64) as usize
+    }
+}
+
+/// `count` names made of characters drawn from `alphabet`, each with a
+/// length in `lengths`.
+pub fn random_names(alphabet: &str, count: usize, lengths: Range<usize>, seed: u64) -> Vec<String> {
+    let chars: Vec<char> = alphabet.chars().collect();
+    let mut rng = Rng::new(seed);
+    (0..count)
+        .map(|_| {
+            let len = lengths.start + rng.below(lengths.len());
+            (0..len).map(|_| chars[rng.below(chars.len())]).collect()
+        })
+        .collect()
+}
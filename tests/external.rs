@@ -0,0 +1,77 @@
+mod common;
+
+use sort_chinese_name::{sort_external, write_names, Collation, NameSorter, OutputFormat, TieBreak, UnknownPolicy};
+use std::io::ErrorKind;
+
+/// Short names from a small alphabet, so there are plenty of ties, plus
+/// delimited, unknown and blank ones.
+fn input() -> String {
+    let names = common::random_names("王李张欧阳司马一三丁已己申A𠀀| ", 500, 0..4, 0x2545_f491_4f6c_dd1d);
+    names.iter().map(|name| format!("{}\n", name)).collect()
+}
+
+fn in_memory(sorter: &NameSorter, input: &str) -> String {
+    let mut names = sort_chinese_name::read_names(input.as_bytes()).unwrap();
+    sorter.try_sort(&mut names).unwrap();
+    let mut out = Vec::new();
+    write_names(&mut out, sorter, &names, &OutputFormat::Lines).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+fn external(sorter: &NameSorter, input: &str, run_len: usize) -> std::io::Result<String> {
+    let mut out = Vec::new();
+    sort_external(sorter, input.as_bytes(), &mut out, run_len)?;
+    Ok(String::from_utf8(out).unwrap())
+}
+
+#[test]
+fn matches_in_memory_sort() {
+    let input = input();
+    for collation in [Collation::Strokes, Collation::StrokeCountOnly, Collation::Pinyin] {
+        for tie_break in [TieBreak::InputOrder, TieBreak::ReverseInputOrder, TieBreak::CodePoint] {
+            let sorter = NameSorter::default().with_collation(collation).with_tie_break(tie_break);
+            let expected = in_memory(&sorter, &input);
+            // A run length of 1 makes more runs than are merged at once.
+            for run_len in [1, 7, 100, 10_000] {
+                assert!(
+                    external(&sorter, &input, run_len).unwrap() == expected,
+                    "{:?}, {:?}, runs of {}",
+                    collation,
+                    tie_break,
+                    run_len
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn returns_unknown_chars_across_runs() {
+    let sorter = NameSorter::default();
+    let mut out = Vec::new();
+    let unknown = sort_external(&sorter, "A丁\n张三\nA一\n𠀀\n".as_bytes(), &mut out, 1).unwrap();
+    let unknown: Vec<_> = unknown.iter().map(|u| (u.ch, u.names.clone())).collect();
+    assert_eq!(unknown, [('A', vec!["A丁".to_string(), "A一".to_string()]), ('𠀀', vec!["𠀀".to_string()])]);
+
+    // Only a sample of the names is kept for each character.
+    let input: String = "一二三四五六七八九十丁王李张赵钱孙周吴郑冯陈褚卫蒋".chars().map(|c| format!("A{}\n", c)).collect();
+    let unknown = sort_external(&sorter, input.as_bytes(), &mut out, 3).unwrap();
+    assert_eq!(unknown.len(), 1);
+    assert_eq!(unknown[0].names.len(), 10);
+    assert_eq!(unknown[0].names[9], "A十");
+}
+
+#[test]
+fn fails_like_try_sort() {
+    let sorter = NameSorter::default().with_unknown_policy(UnknownPolicy::Error);
+    let mut out = Vec::new();
+    let err = sort_external(&sorter, "张三\nA丁\n".as_bytes(), &mut out, 1).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert!(out.is_empty());
+
+    let sorter = NameSorter::default().with_tie_break(TieBreak::Error);
+    assert_eq!(external(&sorter, "王一\n丁一\n王一\n", 1).unwrap(), "丁一\n王一\n王一\n");
+    let err = external(&sorter, "申一\n王一\n甲一\n", 1).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert!(err.to_string().contains("申一"), "{}", err);
+}
@@ -0,0 +1,93 @@
+//! Reference orderings for `Collation::Gb13418`: a published roster sorted
+//! 按姓氏笔画为序, and cases worked out by hand from the rules of GB/T 13418
+//! for characters whose stroke sequences are in data.json.
+
+use sort_chinese_name::{Collation, NameSorter};
+
+fn sorter() -> NameSorter {
+    NameSorter::default().with_collation(Collation::Gb13418)
+}
+
+fn assert_sorted(expected: &[&str]) {
+    let sorter = sorter();
+    let mut names: Vec<String> = expected.iter().rev().map(|s| s.to_string()).collect();
+    sorter.sort(&mut names);
+    assert_eq!(names, expected);
+}
+
+/// The members of the 20th CPC Central Committee Political Bureau, "按姓氏笔画为序",
+/// as announced by Xinhua on 23 October 2022. The roster only defines the
+/// order of surnames; within a surname it follows no rule GB/T 13418 states
+/// (王沪宁 before 王毅, 李强 before 李鸿忠), so only the surnames are compared.
+#[test]
+fn published_roster() {
+    let roster = [
+        "丁薛祥", "习近平", "马兴瑞", "王沪宁", "王毅", "尹力", "石泰峰", "刘国中", "李干杰", "李书磊", "李希", "李强",
+        "李鸿忠", "何卫东", "何立峰", "张又侠", "张国清", "陈文清", "陈吉宁", "陈敏尔", "赵乐际", "袁家军", "黄坤明",
+        "蔡奇",
+    ];
+    let sorter = sorter();
+    let mut names: Vec<String> = roster.iter().rev().map(|s| s.to_string()).collect();
+    sorter.sort(&mut names);
+
+    let surnames = |names: &[String]| -> Vec<String> { names.iter().map(|name| sorter.split(name).0).collect() };
+    let expected: Vec<String> = roster.iter().map(|s| s.to_string()).collect();
+    assert_eq!(names.len(), 24);
+    assert_eq!(surnames(&names), surnames(&expected));
+}
+
+#[test]
+fn stroke_count_comes_first() {
+    assert_sorted(&["丁", "于", "王", "石", "安", "李"]);
+}
+
+#[test]
+fn first_stroke_shape_breaks_ties_in_order_heng_shu_pie_dian_zhe() {
+    // Two strokes: 丁 横, 卜 竖, 刁 折.
+    assert_sorted(&["丁", "卜", "刁"]);
+    // Four strokes: 王 韦 车 横, 毛 撇, 方 点, 尹 孔 邓 折.
+    assert_sorted(&["王", "韦", "车", "毛", "方", "尹", "孔", "邓"]);
+}
+
+#[test]
+fn later_strokes_break_ties_in_turn() {
+    // Three strokes: 于 112, 万 153, 马 551.
+    assert_sorted(&["于", "万", "马"]);
+    // Five strokes: 石 13251, 龙 13534, 卢 21513, 叶 25112, 田 25121, 白 32511.
+    assert_sorted(&["石", "龙", "卢", "叶", "田", "白"]);
+}
+
+#[test]
+fn identical_stroke_sequences_fall_back_to_code_point() {
+    // 己 已 巳 are all 515.
+    assert_sorted(&["己", "已", "巳"]);
+    // 叶 甲 申 are all 25112.
+    assert_sorted(&["叶", "甲", "申"]);
+}
+
+#[test]
+fn surname_is_compared_before_given_name() {
+    assert_sorted(&["丁大伟", "王一", "王丁", "毛一"]);
+}
+
+#[test]
+fn shorter_name_sorts_before_its_extensions() {
+    assert_sorted(&["王一", "王一一", "王丁"]);
+    assert_sorted(&["欧", "欧阳", "欧阳一"]);
+}
+
+#[test]
+fn result_does_not_depend_on_input_order() {
+    let sorter = sorter();
+    let expected = ["丁一", "卜二", "王一", "王一一", "毛泽", "欧阳修", "叶甲", "甲叶", "申一"];
+    let mut reference: Vec<String> = expected.iter().map(|s| s.to_string()).collect();
+    sorter.sort(&mut reference);
+
+    for rotation in 0..expected.len() {
+        let mut names = reference.clone();
+        names.rotate_left(rotation);
+        names.reverse();
+        sorter.sort(&mut names);
+        assert_eq!(names, reference);
+    }
+}
@@ -0,0 +1,47 @@
+use sort_chinese_name::{stroke_heading, write_names, Collation, GroupStyle, NameSorter, OutputFormat};
+
+fn grouped(style: GroupStyle, input: &[&str]) -> String {
+    let sorter = NameSorter::default();
+    let mut names: Vec<String> = input.iter().map(|s| s.to_string()).collect();
+    sorter.sort(&mut names);
+    let mut out = Vec::new();
+    write_names(&mut out, &sorter, &names, &OutputFormat::Grouped(style)).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn headings_use_chinese_numerals() {
+    assert_eq!(stroke_heading(Some(2)), "二画");
+    assert_eq!(stroke_heading(Some(10)), "十画");
+    assert_eq!(stroke_heading(Some(14)), "十四画");
+    assert_eq!(stroke_heading(Some(20)), "二十画");
+    assert_eq!(stroke_heading(Some(23)), "二十三画");
+    assert_eq!(stroke_heading(None), "其他");
+}
+
+#[test]
+fn plain_sections_follow_surname_stroke_count() {
+    assert_eq!(
+        grouped(GroupStyle::Plain, &["王五", "丁二", "毛一", "欧阳修"]),
+        "二画\n丁二\n\n四画\n王五\n毛一\n\n八画\n欧阳修\n"
+    );
+}
+
+#[test]
+fn markdown_and_html_sections() {
+    assert_eq!(grouped(GroupStyle::Markdown, &["王五", "丁二"]), "## 二画\n\n- 丁二\n\n## 四画\n\n- 王五\n");
+    assert_eq!(
+        grouped(GroupStyle::Html, &["王<五>", "丁二"]),
+        "<h2>二画</h2>\n<ul>\n  <li>丁二</li>\n</ul>\n<h2>四画</h2>\n<ul>\n  <li>王&lt;五&gt;</li>\n</ul>\n"
+    );
+}
+
+#[test]
+fn pinyin_order_is_not_grouped_by_strokes() {
+    let sorter = NameSorter::default().with_collation(Collation::Pinyin);
+    let names = vec!["丁二".to_string(), "单一".to_string(), "王五".to_string()];
+    let mut out = Vec::new();
+    let err = write_names(&mut out, &sorter, &names, &OutputFormat::Grouped(GroupStyle::Plain)).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(out.is_empty());
+}
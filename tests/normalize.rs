@@ -0,0 +1,37 @@
+use sort_chinese_name::{NameSorter, Normalization, NormalizedNames};
+use std::cmp::Ordering;
+
+#[test]
+fn compatibility_ideographs_and_radicals_sort_as_ideographs() {
+    let sorter = NameSorter::default();
+    // U+2FA6 KANGXI RADICAL GOLD and U+F9B5 CJK COMPATIBILITY IDEOGRAPH.
+    assert!(sorter.unknown_chars(&["\u{2FA6}城武", "\u{F9B5}子"]).is_empty());
+    assert_eq!(sorter.compare("\u{2FA6}城武", "金城武"), Ordering::Equal);
+    assert_eq!(sorter.stroke_count("\u{F9B5}"), 8);
+    // U+2EC4 and U+2ED1 from the CJK Radicals Supplement, which have no NFKC form.
+    assert!(sorter.unknown_chars(&["\u{2EC4}三", "\u{2ED1}三"]).is_empty());
+    assert_eq!(sorter.canonical_name("\u{2EC4}三"), "西三");
+    assert_eq!(sorter.compare("\u{2ED1}三", "長三"), Ordering::Equal);
+    // ⾧ U+2FA7 is the radical for 長 in the compound surname 長孫.
+    assert_eq!(sorter.split("\u{2FA7}孫無忌"), ("\u{2FA7}孫".to_string(), "無忌".to_string()));
+
+    let none = NameSorter::default().with_normalization(Normalization::None);
+    assert_eq!(none.unknown_chars(&["\u{2FA6}城武"]).len(), 1);
+
+    assert_eq!(Normalization::Nfc.normalize_char('Ａ'), 'Ａ');
+    assert_eq!(Normalization::Nfkc.normalize_char('Ａ'), 'A');
+    assert!("nfd".parse::<Normalization>().is_err());
+}
+
+#[test]
+fn report_names_changed_by_normalization() {
+    let sorter = NameSorter::default();
+    let found = sorter.normalized_names(&["王五", "\u{2FA6}城武", "\u{2FA6}城武", "金城武"]);
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].normalized, "金城武");
+    assert_eq!(
+        NormalizedNames(found).to_string(),
+        "1 name(s) changed by normalization:\n  \u{2FA6}城武 → 金城武 (U+2FA6 → U+91D1)"
+    );
+    assert_eq!(sorter.canonical_name("\u{2FA6}城武"), "金城武");
+}
@@ -0,0 +1,48 @@
+use sort_chinese_name::{write_names, Column, NameSorter, OutputFormat};
+
+fn output(format: OutputFormat, names: &[&str]) -> String {
+    let names: Vec<String> = names.iter().map(|s| s.to_string()).collect();
+    let mut out = Vec::new();
+    write_names(&mut out, &NameSorter::default(), &names, &format).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn lines_and_separated_drop_the_surname_delimiter() {
+    assert_eq!(output(OutputFormat::Lines, &["司|马光", "王五"]), "司马光\n王五\n");
+    assert_eq!(output(OutputFormat::Separated("、".to_string()), &["司|马光", "王五"]), "司马光、王五\n");
+    assert_eq!(output(OutputFormat::Separated(", ".to_string()), &[]), "\n");
+}
+
+#[test]
+fn csv_has_a_header_and_quotes_fields() {
+    let columns = vec![Column::Name, Column::Surname, Column::Given, Column::Strokes];
+    assert_eq!(
+        output(OutputFormat::Csv(columns), &["司|马光", "欧阳修", "王,\"五\""]),
+        "name,surname,given,strokes\n司马光,司,马光,5\n欧阳修,欧阳,修,14\n\"王,\"\"五\"\"\",王,\",\"\"五\"\"\",4\n"
+    );
+}
+
+#[test]
+fn tsv_replaces_tabs_and_newlines() {
+    let columns = vec![Column::Name, Column::Pinyin, Column::Order];
+    assert_eq!(
+        output(OutputFormat::Tsv(columns), &["王五", "王\tA"]),
+        "name\tpinyin\torder\n王五\twang2 wu3\t1121 1251\n王 A\twang2 ? ?\t1121 ? ?\n"
+    );
+}
+
+#[test]
+fn json_is_an_array_of_names_or_objects() {
+    assert_eq!(output(OutputFormat::Json(vec![Column::Name]), &["司|马光"]), "[\n  \"司马光\"\n]\n");
+    assert_eq!(
+        output(OutputFormat::Json(vec![Column::Name, Column::Strokes, Column::Canonical]), &["張偉"]),
+        "[\n  {\n    \"name\": \"張偉\",\n    \"strokes\": 11,\n    \"canonical\": \"张伟\"\n  }\n]\n"
+    );
+}
+
+#[test]
+fn keys_pair_names_with_their_hex_sort_key() {
+    let sorter = NameSorter::default();
+    assert_eq!(output(OutputFormat::Keys, &["王五"]), format!("王五\t{}\n", sorter.sort_key_hex("王五")));
+}
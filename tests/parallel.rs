@@ -0,0 +1,44 @@
+#![cfg(feature = "parallel")]
+
+mod common;
+
+use sort_chinese_name::{Collation, NameSorter, SortError, TieBreak, UnknownPolicy};
+
+/// Many short names from a small alphabet, so there are plenty of ties,
+/// plus delimited and unknown ones.
+fn names() -> Vec<String> {
+    common::random_names("王李张欧阳司马一三丁已己甲申由A𠀀|", 20_000, 1..5, 0x9e37_79b9_7f4a_7c15)
+}
+
+#[test]
+fn par_sort_matches_sort() {
+    let input = names();
+    let collations = [Collation::Strokes, Collation::Gb13418, Collation::StrokeCountOnly, Collation::Pinyin];
+    let tie_breaks = [TieBreak::InputOrder, TieBreak::ReverseInputOrder, TieBreak::CodePoint];
+
+    for collation in collations {
+        for tie_break in tie_breaks {
+            let sorter = NameSorter::default()
+                .with_collation(collation)
+                .with_tie_break(tie_break)
+                .with_unknown_policy(UnknownPolicy::First);
+            let mut sequential = input.clone();
+            sorter.sort(&mut sequential);
+            let mut parallel = input.clone();
+            sorter.par_sort(&mut parallel);
+            assert!(sequential == parallel, "{:?}, {:?}", collation, tie_break);
+        }
+    }
+}
+
+#[test]
+fn par_try_sort_fails_like_try_sort() {
+    let sorter = NameSorter::default().with_unknown_policy(UnknownPolicy::Error);
+    let mut names = vec!["张三".to_string(), "A丁".to_string()];
+    assert!(matches!(sorter.par_try_sort(&mut names), Err(SortError::UnknownChars(_))));
+    assert_eq!(names, ["张三", "A丁"]);
+
+    let sorter = NameSorter::default().with_tie_break(TieBreak::Error);
+    let mut names = vec!["申一".to_string(), "王一".to_string(), "甲一".to_string()];
+    assert!(matches!(sorter.par_try_sort(&mut names), Err(SortError::Tie(..))));
+}
@@ -0,0 +1,80 @@
+use sort_chinese_name::{read_pinyin_dict, read_surname_readings, Collation, NameSorter, SurnameReadings};
+
+fn assert_sorted(expected: &[&str]) {
+    let sorter = NameSorter::default().with_collation(Collation::Pinyin);
+    let mut names: Vec<String> = expected.iter().rev().map(|s| s.to_string()).collect();
+    sorter.sort(&mut names);
+    assert_eq!(names, expected);
+}
+
+#[test]
+fn syllables_are_compared_alphabetically() {
+    assert_sorted(&["安", "李四", "王五", "张三", "赵六"]);
+    // v stands for ü and sorts after u.
+    assert_sorted(&["卢布", "吕布"]);
+}
+
+#[test]
+fn tone_breaks_ties_between_equal_syllables() {
+    // 妈 ma1, 麻 ma2, 马 ma3, 骂 ma4.
+    assert_sorted(&["妈", "麻", "马", "骂"]);
+}
+
+#[test]
+fn strokes_break_ties_between_equal_readings() {
+    // 张 and 章 are both zhang1, with 7 and 11 strokes.
+    assert_sorted(&["张三", "章三"]);
+}
+
+#[test]
+fn surname_is_compared_before_given_name() {
+    // The surname 欧 is a prefix of 欧阳, so 欧一 comes first.
+    assert_sorted(&["欧一", "欧阳修", "王安"]);
+    assert_sorted(&["张三", "张山", "章三"]);
+}
+
+#[test]
+fn dictionary_file_format() {
+    let dict = read_pinyin_dict("# comment\n张 zhang1\n吕 lv3\n".as_bytes()).unwrap();
+    assert_eq!(dict.get('张'), Some(("zhang", 1)));
+    assert_eq!(dict.get('吕'), Some(("lv", 3)));
+    assert!(read_pinyin_dict("张 zhang\n".as_bytes()).is_err());
+    assert!(read_pinyin_dict("张 Zhang1\n".as_bytes()).is_err());
+    assert!(read_pinyin_dict("张 lü\n".as_bytes()).is_err());
+}
+
+#[test]
+fn polyphonic_surnames_use_their_surname_reading() {
+    // 单 shàn, 仇 qiú, 区 ōu, 解 xiè, 朴 piáo, 尉迟 yùchí, 曾 zēng.
+    assert_sorted(&["蔡一", "区小明", "朴树", "仇英", "单雄信", "解缙", "尉迟恭", "曾国藩", "周一"]);
+}
+
+#[test]
+fn given_names_keep_the_everyday_reading() {
+    let sorter = NameSorter::default();
+    assert_eq!(sorter.pinyin("单单"), [Some(("shan", 4)), Some(("dan", 1))]);
+    assert_eq!(sorter.pinyin("尉迟恭"), [Some(("yu", 4)), Some(("chi", 2)), Some(("gong", 1))]);
+    // 尉 alone is read wèi.
+    assert_eq!(sorter.pinyin("尉缭")[0], Some(("wei", 4)));
+}
+
+#[test]
+fn surname_readings_file_format() {
+    let table = read_surname_readings("# comment\n尉迟 yu4 chi2\n单 shan4\n".as_bytes()).unwrap();
+    assert_eq!(table.get("单"), Some(&[("shan".to_string(), 4)][..]));
+    assert_eq!(table.get("尉迟").map(<[_]>::len), Some(2));
+    assert!(read_surname_readings("尉迟 yu4\n".as_bytes()).is_err());
+    assert!(read_surname_readings("吕 lǚ\n".as_bytes()).is_err());
+}
+
+#[test]
+fn surname_readings_shorter_than_the_surname_fall_back_to_the_dictionary() {
+    let mut readings = SurnameReadings::default();
+    readings.insert("尉迟".into(), vec![("yu".into(), 4)]);
+    let sorter = NameSorter::default().with_collation(Collation::Pinyin).with_surname_readings(readings);
+    assert_eq!(sorter.pinyin("尉迟恭"), [Some(("yu", 4)), Some(("chi", 2)), Some(("gong", 1))]);
+
+    let mut names = vec!["尉迟恭".to_string(), "蔡一".to_string(), "周一".to_string()];
+    sorter.sort(&mut names);
+    assert_eq!(names, ["蔡一", "尉迟恭", "周一"]);
+}
@@ -0,0 +1,60 @@
+use serde_json::{json, Value};
+use sort_chinese_name::{read_records, sort_records, write_records, FieldPath, NameSorter, RecordFormat};
+
+fn sorted(records: Value, path: &str) -> Value {
+    let Value::Array(mut records) = records else { unreachable!() };
+    sort_records(&NameSorter::default(), &mut records, &path.parse().unwrap()).unwrap();
+    Value::Array(records)
+}
+
+#[test]
+fn sorts_records_by_field_path() {
+    let records = json!([
+        {"id": 1, "person": {"name": "张三"}},
+        {"id": 2, "person": {"name": "丁一"}},
+        {"id": 3, "person": {}},
+        {"id": 4, "person": {"name": "欧阳修", "dept": "人事"}},
+    ]);
+    assert_eq!(
+        sorted(records, "person.name"),
+        json!([
+            {"id": 3, "person": {}},
+            {"id": 2, "person": {"name": "丁一"}},
+            {"id": 1, "person": {"name": "张三"}},
+            {"id": 4, "person": {"name": "欧阳修", "dept": "人事"}},
+        ])
+    );
+    assert_eq!(sorted(json!(["张三", "丁一"]), "."), json!(["丁一", "张三"]));
+    assert_eq!(sorted(json!([{"names": ["张三"]}, {"names": ["丁一"]}]), "names.0"), json!([{"names": ["丁一"]}, {"names": ["张三"]}]));
+    assert!("person..name".parse::<FieldPath>().is_err());
+}
+
+#[test]
+fn json_lines_round_trip() {
+    let input = "{\"name\":\"张三\",\"age\":30}\n\n{\"name\":\"丁一\",\"tags\":[\"a\"]}\n";
+    let sorter = NameSorter::default();
+    let path = "name".parse().unwrap();
+    let mut records = read_records(input.as_bytes(), RecordFormat::JsonLines).unwrap();
+    sort_records(&sorter, &mut records, &path).unwrap();
+    let mut out = Vec::new();
+    write_records(&mut out, &sorter, &records, &path, RecordFormat::JsonLines).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), "{\"name\":\"丁一\",\"tags\":[\"a\"]}\n{\"name\":\"张三\",\"age\":30}\n");
+
+    let err = read_records("{\"name\":\"张三\"}\n{oops}\n".as_bytes(), RecordFormat::JsonLines).unwrap_err();
+    assert!(err.to_string().starts_with("line 2:"), "{}", err);
+    assert!(read_records("{\"name\":\"张三\"}".as_bytes(), RecordFormat::Json).is_err());
+}
+
+#[test]
+fn names_are_written_without_the_surname_delimiter() {
+    let sorter = NameSorter::default();
+    let path: FieldPath = "person.name".parse().unwrap();
+    let mut records = vec![json!({"person": {"name": "司|马光"}, "note": "a|b"}), json!({"person": {"name": "李一"}})];
+    sort_records(&sorter, &mut records, &path).unwrap();
+    let mut out = Vec::new();
+    write_records(&mut out, &sorter, &records, &path, RecordFormat::Json).unwrap();
+    let written: Value = serde_json::from_slice(&out).unwrap();
+    assert_eq!(written, json!([{"person": {"name": "司马光"}, "note": "a|b"}, {"person": {"name": "李一"}}]));
+    // The records themselves keep the delimiter.
+    assert_eq!(records[0]["person"]["name"], "司|马光");
+}
@@ -0,0 +1,55 @@
+use sort_chinese_name::{Collation, NameSorter, TieBreak, UnknownPolicy};
+
+const NAMES: [&str; 22] = [
+    "丁丁", "李思", "张二", "张三丰", "张四", "张瑜", "张瑜田", "测试", "秦玉米", "申一", "叶一", "甲一", "己", "已",
+    "欧阳", "欧阳修", "欧一", "司|马光", "单雄信", "𠀀一", "A丁", "",
+];
+
+#[test]
+fn sort_key_order_matches_compare() {
+    let collations = [Collation::Strokes, Collation::Gb13418, Collation::StrokeCountOnly, Collation::Pinyin];
+    let policies = [UnknownPolicy::Last, UnknownPolicy::First, UnknownPolicy::Strokes(3)];
+    let tie_breaks = [TieBreak::InputOrder, TieBreak::CodePoint];
+
+    for collation in collations {
+        for policy in policies {
+            for tie_break in tie_breaks {
+                let sorter = NameSorter::default()
+                    .with_collation(collation)
+                    .with_unknown_policy(policy)
+                    .with_tie_break(tie_break);
+                for a in NAMES {
+                    for b in NAMES {
+                        let mut expected = sorter.compare(a, b);
+                        if tie_break == TieBreak::CodePoint {
+                            expected = expected.then_with(|| a.cmp(b));
+                        }
+                        assert_eq!(
+                            sorter.sort_key(a).cmp(&sorter.sort_key(b)),
+                            expected,
+                            "{} vs {} under {:?}, {:?}, {:?}",
+                            a,
+                            b,
+                            collation,
+                            policy,
+                            tie_break
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn hex_keys_sort_like_the_names() {
+    let sorter = NameSorter::default().with_tie_break(TieBreak::CodePoint);
+    let mut names: Vec<String> = NAMES.iter().map(|s| s.to_string()).collect();
+    sorter.sort(&mut names);
+
+    let mut by_key: Vec<(String, String)> =
+        NAMES.iter().map(|name| (sorter.sort_key_hex(name), name.to_string())).collect();
+    by_key.sort();
+    let by_key: Vec<String> = by_key.into_iter().map(|(_, name)| name).collect();
+    assert_eq!(by_key, names);
+}
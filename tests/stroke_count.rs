@@ -0,0 +1,31 @@
+use sort_chinese_name::{Collation, NameSorter};
+
+fn sorted(names: &[&str]) -> Vec<String> {
+    let sorter = NameSorter::default().with_collation(Collation::StrokeCountOnly);
+    let mut names: Vec<String> = names.iter().map(|s| s.to_string()).collect();
+    sorter.sort(&mut names);
+    names
+}
+
+#[test]
+fn stroke_count_decides_and_ties_keep_input_order() {
+    // 王 毛 方 孔 all have four strokes, 丁 two, 李 seven.
+    assert_eq!(
+        sorted(&["孔一", "王一", "李一", "方一", "丁一", "毛一"]),
+        ["丁一", "孔一", "王一", "方一", "毛一", "李一"]
+    );
+    assert_eq!(sorted(&["毛一", "方一", "王一", "孔一"]), ["毛一", "方一", "王一", "孔一"]);
+}
+
+#[test]
+fn stroke_shapes_are_ignored() {
+    let sorter = NameSorter::default().with_collation(Collation::StrokeCountOnly);
+    assert_eq!(sorter.compare("王", "毛"), std::cmp::Ordering::Equal);
+    assert_eq!(sorter.compare("己", "巳"), std::cmp::Ordering::Equal);
+}
+
+#[test]
+fn given_name_counts_break_surname_ties() {
+    // 一 has one stroke, 三 three.
+    assert_eq!(sorted(&["毛三", "王一", "方三"]), ["王一", "毛三", "方三"]);
+}
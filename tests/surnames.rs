@@ -0,0 +1,87 @@
+use sort_chinese_name::{embedded_compound_surnames_set, read_compound_surnames_set, NameSorter, StrokeDict, SurnameTrie};
+
+#[test]
+fn comments_blank_lines_and_inner_whitespace_are_ignored() {
+    let list = "# header\n\n欧阳 # trailing comment\n司 马\n欧阳\n";
+    let surnames = read_compound_surnames_set(list.as_bytes()).unwrap();
+    assert_eq!(surnames.len(), 2);
+    assert!(surnames.contains("欧阳"));
+    assert!(surnames.contains("司马"));
+}
+
+#[test]
+fn entries_that_are_not_two_cjk_characters_are_reported_by_line() {
+    let list = "欧阳\n宰\n父\nAB\n";
+    let message = read_compound_surnames_set(list.as_bytes()).unwrap_err().to_string();
+    assert!(message.contains("line 2: `宰`"), "{}", message);
+    assert!(message.contains("line 3: `父`"), "{}", message);
+    assert!(message.contains("line 4: `AB`"), "{}", message);
+    assert!(!message.contains("line 1"), "{}", message);
+}
+
+#[test]
+fn embedded_list_contains_repaired_entries() {
+    let surnames = embedded_compound_surnames_set();
+    assert!(surnames.contains("宰父"));
+    assert!(!surnames.contains("宰"));
+    assert_eq!(
+        NameSorter::default().split("宰父一"),
+        ("宰父".to_string(), "一".to_string())
+    );
+}
+
+fn split(surnames: &[&str], name: &str) -> (String, String) {
+    let trie: SurnameTrie = surnames.iter().collect();
+    NameSorter::new(StrokeDict::embedded(), trie).split(name)
+}
+
+fn parts(surname: &str, given: &str) -> (String, String) {
+    (surname.to_string(), given.to_string())
+}
+
+#[test]
+fn single_character_surname_is_the_default() {
+    assert_eq!(split(&["欧阳"], "王小明"), parts("王", "小明"));
+    assert_eq!(split(&["欧阳"], "欧小明"), parts("欧", "小明"));
+}
+
+#[test]
+fn two_character_surname_is_matched() {
+    assert_eq!(split(&["欧阳"], "欧阳修"), parts("欧阳", "修"));
+    assert_eq!(split(&["张王"], "张王芳"), parts("张王", "芳"));
+}
+
+#[test]
+fn three_character_surname_is_matched() {
+    assert_eq!(split(&["爱新觉罗"], "爱新觉罗玄烨"), parts("爱新觉罗", "玄烨"));
+    assert_eq!(split(&["步六孤"], "步六孤俊"), parts("步六孤", "俊"));
+}
+
+#[test]
+fn longest_matching_surname_wins() {
+    let surnames = ["欧阳", "欧阳张", "欧阳张王"];
+    assert_eq!(split(&surnames, "欧阳张三"), parts("欧阳张", "三"));
+    assert_eq!(split(&surnames, "欧阳张王三"), parts("欧阳张王", "三"));
+    assert_eq!(split(&surnames, "欧阳三"), parts("欧阳", "三"));
+    // A partial match that is not itself a surname falls back to the shorter one.
+    assert_eq!(split(&["欧阳", "欧阳张王"], "欧阳张三"), parts("欧阳", "张三"));
+}
+
+#[test]
+fn longer_entries_are_accepted_by_the_loader() {
+    let surnames = read_compound_surnames_set("爱新觉罗\n步六孤\n".as_bytes()).unwrap();
+    assert!(surnames.contains("爱新觉罗"));
+    assert!(surnames.contains("步六孤"));
+}
+
+#[test]
+fn explicit_delimiter_overrides_the_surname_list() {
+    let sorter = NameSorter::default();
+    assert_eq!(sorter.split("司马光"), parts("司马", "光"));
+    assert_eq!(sorter.split("司|马光"), parts("司", "马光"));
+    assert_eq!(sorter.split("张|王芳"), parts("张", "王芳"));
+    assert_eq!(sorter.display_name("司|马光"), "司马光");
+
+    let sorter = NameSorter::default().with_surname_delimiter(None);
+    assert_eq!(sorter.split("司|马光"), parts("司", "|马光"));
+}
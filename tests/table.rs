@@ -0,0 +1,80 @@
+use sort_chinese_name::{read_table, write_table, ColumnRef, NameSorter, TableFormat, TableKey, TieBreak};
+
+const CSV: &str = "\u{feff}id,部门,姓名\n1,人事,张三\n2,\"财务,审计\",丁一\n3,人事,欧阳修\n4,行政,司|马光\n";
+
+fn sorted(input: &str, format: TableFormat, column: &str, has_header: bool) -> String {
+    let sorter = NameSorter::default();
+    let mut table = read_table(input.as_bytes(), format, has_header).unwrap();
+    let index = table.column_index(&column.parse::<ColumnRef>().unwrap()).unwrap();
+    table.sort(&sorter, index).unwrap();
+    let mut out = Vec::new();
+    write_table(&mut out, &sorter, &table, index, format).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn sorts_rows_by_name_column_and_keeps_other_columns() {
+    let expected = "id,部门,姓名\n2,\"财务,审计\",丁一\n4,行政,司马光\n1,人事,张三\n3,人事,欧阳修\n";
+    assert_eq!(sorted(CSV, TableFormat::Csv, "姓名", true), expected);
+    assert_eq!(sorted(CSV, TableFormat::Csv, "3", true), expected);
+}
+
+#[test]
+fn tsv_without_header() {
+    let tsv = "张三\t1\n丁一\t2\t额外\n王五\n";
+    assert_eq!(sorted(tsv, TableFormat::Tsv, "1", false), "丁一\t2\t额外\n王五\n张三\t1\n");
+}
+
+#[test]
+fn missing_columns_are_errors() {
+    let table = read_table(CSV.as_bytes(), TableFormat::Csv, true).unwrap();
+    assert!(table.column_index(&ColumnRef::Header("name".to_string())).is_err());
+    assert!(table.column_index(&ColumnRef::Index(3)).is_err());
+
+    let table = read_table(CSV.as_bytes(), TableFormat::Csv, false).unwrap();
+    assert!(table.column_index(&ColumnRef::Header("姓名".to_string())).is_err());
+    assert_eq!(table.column_index(&ColumnRef::Index(2)).unwrap(), 2);
+}
+
+const ROSTER: &str = "部门,年级,姓名\n人事,3,张三\n财务,10,王五\n人事,10,丁一\n财务,3,李四\n人事,,欧阳修\n";
+
+fn sorted_by(keys: &[&str], sorter: &NameSorter) -> std::io::Result<Vec<String>> {
+    let mut table = read_table(ROSTER.as_bytes(), TableFormat::Csv, true).unwrap();
+    let keys: Vec<TableKey> = keys.iter().map(|key| key.parse().unwrap()).collect();
+    table.sort_by_keys(sorter, &keys)?;
+    Ok(table.rows.iter().map(|row| row.join(" ")).collect())
+}
+
+#[test]
+fn sorts_by_several_keys() {
+    let sorter = NameSorter::default();
+    assert_eq!(
+        sorted_by(&["部门", "姓名:name"], &sorter).unwrap(),
+        ["人事 10 丁一", "人事 3 张三", "人事  欧阳修", "财务 10 王五", "财务 3 李四"]
+    );
+    // Numbers compare as numbers, and cells that are not numbers come last.
+    assert_eq!(
+        sorted_by(&["部门:desc", "年级:number"], &sorter).unwrap(),
+        ["财务 3 李四", "财务 10 王五", "人事 3 张三", "人事 10 丁一", "人事  欧阳修"]
+    );
+    assert_eq!(
+        sorted_by(&["2:number:desc", "3:name"], &sorter).unwrap(),
+        ["人事 10 丁一", "财务 10 王五", "财务 3 李四", "人事 3 张三", "人事  欧阳修"]
+    );
+}
+
+#[test]
+fn sort_keys_follow_tie_break() {
+    let sorter = NameSorter::default().with_tie_break(TieBreak::ReverseInputOrder);
+    assert_eq!(
+        sorted_by(&["部门"], &sorter).unwrap(),
+        ["人事  欧阳修", "人事 10 丁一", "人事 3 张三", "财务 3 李四", "财务 10 王五"]
+    );
+
+    let sorter = NameSorter::default().with_tie_break(TieBreak::Error);
+    assert!(sorted_by(&["部门", "姓名:name"], &sorter).is_ok());
+    let mut table = read_table("申一\n甲一\n".as_bytes(), TableFormat::Csv, false).unwrap();
+    let err = table.sort_by_keys(&sorter, &["1:name".parse().unwrap()]).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert!("部门:numeric".parse::<TableKey>().is_err());
+}
@@ -0,0 +1,74 @@
+use sort_chinese_name::{Collation, NameSorter, SortError, TieBreak};
+
+// 叶 甲 申 share the stroke sequence 25112, so these tie under `Strokes`.
+const TIES: [&str; 3] = ["申一", "叶一", "甲一"];
+
+fn names(names: &[&str]) -> Vec<String> {
+    names.iter().map(|s| s.to_string()).collect()
+}
+
+fn sorted(tie_break: TieBreak, input: &[&str]) -> Vec<String> {
+    let mut names = names(input);
+    NameSorter::default().with_tie_break(tie_break).sort(&mut names);
+    names
+}
+
+#[test]
+fn input_order_is_the_default() {
+    let mut names = names(&TIES);
+    NameSorter::default().sort(&mut names);
+    assert_eq!(names, TIES);
+    assert_eq!(sorted(TieBreak::InputOrder, &TIES), TIES);
+}
+
+#[test]
+fn reverse_input_order() {
+    assert_eq!(sorted(TieBreak::ReverseInputOrder, &TIES), ["甲一", "叶一", "申一"]);
+    // Names that do not tie are unaffected.
+    assert_eq!(sorted(TieBreak::ReverseInputOrder, &["丁一", "王一"]), ["丁一", "王一"]);
+}
+
+#[test]
+fn code_point_order() {
+    assert_eq!(sorted(TieBreak::CodePoint, &TIES), ["叶一", "甲一", "申一"]);
+}
+
+#[test]
+fn error_on_tie() {
+    let sorter = NameSorter::default().with_tie_break(TieBreak::Error);
+    let mut tied = names(&TIES);
+    assert!(matches!(sorter.try_sort(&mut tied), Err(SortError::Tie(..))));
+
+    // Identical names are not a tie worth reporting.
+    let mut untied = names(&["王一", "丁一", "王一"]);
+    sorter.try_sort(&mut untied).unwrap();
+    assert_eq!(untied, ["丁一", "王一", "王一"]);
+}
+
+#[test]
+fn code_point_tie_break_is_deterministic_under_every_collation() {
+    let input = [
+        "申一", "叶一", "甲一", "王一", "毛一", "己", "已", "巳", "欧阳修", "欧一", "张三", "章三", "单雄信", "𠀀一",
+        "A",
+    ];
+    for collation in [Collation::Strokes, Collation::Gb13418, Collation::StrokeCountOnly, Collation::Pinyin] {
+        let sorter = NameSorter::default()
+            .with_collation(collation)
+            .with_tie_break(TieBreak::CodePoint);
+        let mut reference = names(&input);
+        sorter.sort(&mut reference);
+
+        for rotation in 0..input.len() {
+            for reversed in [false, true] {
+                let mut permuted = names(&input);
+                permuted.rotate_left(rotation);
+                if reversed {
+                    permuted.reverse();
+                }
+                permuted.swap(0, input.len() / 2);
+                sorter.sort(&mut permuted);
+                assert_eq!(permuted, reference, "{:?}", collation);
+            }
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use sort_chinese_name::{read_variants, Collation, NameSorter, VariantDuplicates, VariantMap};
+use std::cmp::Ordering;
+
+fn names(names: &[&str]) -> Vec<String> {
+    names.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn traditional_characters_have_their_own_strokes() {
+    let sorter = NameSorter::default();
+    assert!(sorter.unknown_chars(&["陳大文", "劉德華", "黃一", "鄭二", "蝦米"]).is_empty());
+    assert_eq!(sorter.stroke_count("陈"), 7);
+    assert_eq!(sorter.stroke_count("陳"), 10);
+    assert_eq!(sorter.split("歐陽修"), ("歐陽".to_string(), "修".to_string()));
+
+    let pinyin = NameSorter::default().with_collation(Collation::Pinyin);
+    assert_eq!(pinyin.pinyin("單雄信")[0], Some(("shan", 4)));
+}
+
+#[test]
+fn fold_variants_sorts_by_simplified_strokes() {
+    let input = names(&["陳大文", "刘一", "陈小明", "劉德華"]);
+
+    let mut sorted = input.clone();
+    NameSorter::default().sort(&mut sorted);
+    assert_eq!(sorted, ["刘一", "陈小明", "陳大文", "劉德華"]);
+
+    let sorter = NameSorter::default().with_fold_variants(true);
+    let mut sorted = input.clone();
+    sorter.sort(&mut sorted);
+    assert_eq!(sorted, ["刘一", "劉德華", "陳大文", "陈小明"]);
+    assert_eq!(sorter.compare("陳大文", "陈大文"), Ordering::Equal);
+    assert_eq!(sorter.stroke_count("陳"), 7);
+    assert_ne!(NameSorter::default().compare("陳大文", "陈大文"), Ordering::Equal);
+}
+
+#[test]
+fn custom_variant_table() {
+    let variants = read_variants("# 异体字\n峯 峰\n".as_bytes()).unwrap();
+    assert_eq!(variants.get('峯'), Some('峰'));
+    assert_eq!(variants.fold("高峯"), "高峰");
+    assert!(VariantMap::embedded().len() > 2000);
+
+    let sorter = NameSorter::default().with_variants(variants).with_fold_variants(true);
+    assert_eq!(sorter.compare("李峯", "李峰"), Ordering::Equal);
+
+    let err = read_variants("峯 峰\n峯峰\n".as_bytes()).err().unwrap();
+    assert!(err.to_string().starts_with("line 2:"), "{}", err);
+}
+
+#[test]
+fn variant_duplicates_and_canonical_names() {
+    let sorter = NameSorter::default();
+    assert_eq!(sorter.canonical_name("張|偉"), "张伟");
+    assert_eq!(sorter.canonical_name("高峯"), "高峰");
+
+    let found = sorter.variant_duplicates(&["张伟", "李峰", "張偉", "王五", "李峯", "张伟", "張|偉"]);
+    assert_eq!(found, [vec!["张伟", "張偉"], vec!["李峰", "李峯"]]);
+    assert_eq!(
+        VariantDuplicates(found).to_string(),
+        "2 name(s) written with different variants:\n  张伟 = 張偉\n  李峰 = 李峯"
+    );
+}
@@ -0,0 +1,51 @@
+use sort_chinese_name::{Collation, NameSorter, SortError, UnknownChars, UnknownPolicy};
+
+fn sorted(policy: UnknownPolicy, input: &[&str]) -> Vec<String> {
+    let mut names: Vec<String> = input.iter().map(|s| s.to_string()).collect();
+    NameSorter::default().with_unknown_policy(policy).sort(&mut names);
+    names
+}
+
+#[test]
+fn policy_places_unknown_characters() {
+    let input = ["李一", "A一", "王一", "毛一", "王A", "王丁"];
+    assert_eq!(sorted(UnknownPolicy::Last, &input), ["王一", "王丁", "王A", "毛一", "李一", "A一"]);
+    assert_eq!(sorted(UnknownPolicy::First, &input), ["A一", "王A", "王一", "王丁", "毛一", "李一"]);
+    // Four strokes, after 王 and 毛 and before the seven strokes of 李.
+    assert_eq!(sorted(UnknownPolicy::Strokes(4), &input), ["王一", "王丁", "王A", "毛一", "A一", "李一"]);
+    // Under the error policy plain sort treats them as last.
+    assert_eq!(sorted(UnknownPolicy::Error, &input), sorted(UnknownPolicy::Last, &input));
+}
+
+#[test]
+fn unknown_characters_are_ordered_by_code_point() {
+    assert_eq!(sorted(UnknownPolicy::Last, &["B", "𠀀", "A"]), ["A", "B", "𠀀"]);
+}
+
+#[test]
+fn error_policy_fails_try_sort() {
+    let sorter = NameSorter::default().with_unknown_policy(UnknownPolicy::Error);
+    let mut names: Vec<String> = ["李一", "A一", "王A", "王一"].iter().map(|s| s.to_string()).collect();
+    let err = sorter.try_sort(&mut names).unwrap_err();
+    assert_eq!(names, ["李一", "A一", "王A", "王一"]);
+
+    let SortError::UnknownChars(UnknownChars(unknown, _)) = &err else {
+        panic!("{:?}", err);
+    };
+    assert_eq!(unknown.len(), 1);
+    assert_eq!(unknown[0].ch, 'A');
+    assert_eq!(unknown[0].names, ["A一", "王A"]);
+    assert_eq!(err.to_string(), "1 character(s) not in the stroke dictionary:\n  A (U+0041): A一, 王A");
+
+    let mut known = vec!["李一".to_string(), "王一".to_string()];
+    sorter.try_sort(&mut known).unwrap();
+    assert_eq!(known, ["王一", "李一"]);
+}
+
+#[test]
+fn report_names_the_dictionary_of_the_collation() {
+    let sorter = NameSorter::default().with_collation(Collation::Pinyin).with_unknown_policy(UnknownPolicy::Error);
+    let mut names = vec!["李一".to_string(), "王A".to_string()];
+    let err = sorter.try_sort(&mut names).unwrap_err();
+    assert_eq!(err.to_string(), "1 character(s) not in the pinyin dictionary:\n  A (U+0041): 王A");
+}
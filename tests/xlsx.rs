@@ -0,0 +1,94 @@
+#![cfg(feature = "xlsx")]
+
+use rust_xlsxwriter::{ExcelDateTime, Format, Workbook};
+use sort_chinese_name::{read_xlsx, write_xlsx, CellKind, Column, NameSorter};
+use std::io::Cursor;
+
+/// A workbook whose table starts at B2, with a second sheet.
+fn workbook() -> Vec<u8> {
+    let mut workbook = Workbook::new();
+    let sheet = workbook.add_worksheet().set_name("名单").unwrap();
+    for (column, header) in ["工号", "姓名", "入职", "在职"].iter().enumerate() {
+        sheet.write_string(1, 1 + column as u16, *header).unwrap();
+    }
+    let date = Format::new().set_num_format("yyyy-mm-dd");
+    let rows = [(7.0, "张三", 2024, true), (12.0, "丁一", 2023, false), (3.5, "欧阳|修", 2022, true)];
+    for (i, (id, name, year, active)) in rows.iter().enumerate() {
+        let row = 2 + i as u32;
+        sheet.write_number(row, 1, *id).unwrap();
+        sheet.write_string(row, 2, *name).unwrap();
+        sheet.write_datetime_with_format(row, 3, ExcelDateTime::from_ymd(*year, 9, 1).unwrap(), &date).unwrap();
+        sheet.write_boolean(row, 4, *active).unwrap();
+    }
+    workbook.add_worksheet().set_name("备注").unwrap().write_string(0, 0, "无").unwrap();
+    workbook.save_to_buffer().unwrap()
+}
+
+#[test]
+fn reads_sheet_cells_as_text() {
+    let table = read_xlsx(Cursor::new(workbook()), None, true).unwrap();
+    assert_eq!(table.header.unwrap(), ["", "工号", "姓名", "入职", "在职"]);
+    assert_eq!(table.rows[0], ["", "7", "张三", "2024-09-01", "TRUE"]);
+    assert_eq!(table.rows[2], ["", "3.5", "欧阳|修", "2022-09-01", "TRUE"]);
+    use CellKind::*;
+    assert_eq!(table.kinds[0], [Text, Number, Text, Date, Bool]);
+
+    let table = read_xlsx(Cursor::new(workbook()), Some("备注"), false).unwrap();
+    assert_eq!(table.rows, [["无"]]);
+    assert!(read_xlsx(Cursor::new(workbook()), Some("Sheet9"), true).is_err());
+}
+
+#[test]
+fn writes_sorted_rows_with_added_columns() {
+    let sorter = NameSorter::default();
+    let mut table = read_xlsx(Cursor::new(workbook()), None, true).unwrap();
+    table.sort(&sorter, 2).unwrap();
+    table.add_columns(&sorter, 2, &[Column::Surname, Column::Strokes]);
+
+    let mut out = Vec::new();
+    write_xlsx(&mut out, &sorter, &table, 2).unwrap();
+    let written = read_xlsx(Cursor::new(out), None, true).unwrap();
+    assert_eq!(written.header.unwrap(), ["", "工号", "姓名", "入职", "在职", "surname", "strokes"]);
+    assert_eq!(
+        written.rows,
+        [
+            ["", "12", "丁一", "2023-09-01", "FALSE", "丁", "2"],
+            ["", "7", "张三", "2024-09-01", "TRUE", "张", "7"],
+            ["", "3.5", "欧阳修", "2022-09-01", "TRUE", "欧阳", "14"],
+        ]
+    );
+}
+
+#[test]
+fn keeps_cell_types() {
+    let mut workbook = Workbook::new();
+    let sheet = workbook.add_worksheet();
+    let datetime = Format::new().set_num_format("yyyy-mm-dd hh:mm");
+    let rows = [("王五", "007", "13800138000", 3.0), ("丁一", "12", "010-1234", 1.0)];
+    for (i, (name, id, phone, day)) in rows.iter().enumerate() {
+        let row = i as u32;
+        sheet.write_string(row, 0, *name).unwrap();
+        sheet.write_string(row, 1, *id).unwrap();
+        sheet.write_string(row, 2, *phone).unwrap();
+        let at = ExcelDateTime::from_ymd(2024, 9, *day as u8).unwrap().and_hms(8, 30, 0).unwrap();
+        sheet.write_datetime_with_format(row, 3, at, &datetime).unwrap();
+    }
+
+    let sorter = NameSorter::default();
+    let mut table = read_xlsx(Cursor::new(workbook.save_to_buffer().unwrap()), None, false).unwrap();
+    table.sort(&sorter, 0).unwrap();
+    table.add_columns(&sorter, 0, &[Column::Strokes]);
+    let mut out = Vec::new();
+    write_xlsx(&mut out, &sorter, &table, 0).unwrap();
+
+    let written = read_xlsx(Cursor::new(out), None, false).unwrap();
+    assert_eq!(
+        written.rows,
+        [
+            ["丁一", "12", "010-1234", "2024-09-01 08:30:00", "2"],
+            ["王五", "007", "13800138000", "2024-09-03 08:30:00", "4"],
+        ]
+    );
+    use CellKind::*;
+    assert_eq!(written.kinds, [[Text, Text, Text, DateTime, Number], [Text, Text, Text, DateTime, Number]]);
+}